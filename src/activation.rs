@@ -0,0 +1,118 @@
+use num_traits::Float;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// The activation function applied by a neuron to its weighted sum.
+///
+/// Serialized as its lowercase name ("sigmoid", "relu", "tanh", "swish", "softplus", "linear"), which keeps
+/// saved models readable. Parsing an unknown name is an error rather than a silent
+/// fallback to linear.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum ActivationFunction {
+    Sigmoid,
+    Relu,
+    Tanh,
+    Swish,
+    Softplus,
+    Linear,
+}
+
+impl ActivationFunction {
+    pub fn apply<T: Float>(&self, value: T) -> T {
+        match self {
+            ActivationFunction::Sigmoid => sigmoid(value),
+            ActivationFunction::Relu => value.max(T::zero()),
+            ActivationFunction::Tanh => value.tanh(),
+            ActivationFunction::Swish => value * sigmoid(value),
+            // ln(1 + e^x) rewritten as max(x, 0) + ln(1 + e^-|x|) so large inputs cannot overflow.
+            ActivationFunction::Softplus => value.max(T::zero()) + (-value.abs()).exp().ln_1p(),
+            ActivationFunction::Linear => value,
+        }
+    }
+
+    /// The derivative of the activation at `pre_activation`, whose image is `output`.
+    ///
+    /// Most activations only need the output; swish and softplus need the raw pre-activation.
+    pub fn derivative<T: Float>(&self, pre_activation: T, output: T) -> T {
+        match self {
+            ActivationFunction::Sigmoid => output * (T::one() - output),
+            ActivationFunction::Relu => {
+                if output > T::zero() {
+                    T::one()
+                } else {
+                    T::zero()
+                }
+            }
+            ActivationFunction::Tanh => T::one() - output * output,
+            ActivationFunction::Swish => {
+                let sigmoid = sigmoid(pre_activation);
+                sigmoid + pre_activation * sigmoid * (T::one() - sigmoid)
+            }
+            ActivationFunction::Softplus => sigmoid(pre_activation),
+            ActivationFunction::Linear => T::one(),
+        }
+    }
+
+    /// An upper bound on the slope of the activation, used by `NeuralNetwork::lipschitz_bound`.
+    ///
+    /// 1 for every activation but swish, whose derivative peaks at about 1.0998.
+    pub fn lipschitz_constant(&self) -> f32 {
+        match self {
+            ActivationFunction::Swish => 1.1,
+            _ => 1.0,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ActivationFunction::Sigmoid => "sigmoid",
+            ActivationFunction::Relu => "relu",
+            ActivationFunction::Tanh => "tanh",
+            ActivationFunction::Swish => "swish",
+            ActivationFunction::Softplus => "softplus",
+            ActivationFunction::Linear => "linear",
+        }
+    }
+}
+
+fn sigmoid<T: Float>(value: T) -> T {
+    T::one() / (T::one() + (-value).exp())
+}
+
+impl fmt::Display for ActivationFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for ActivationFunction {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "sigmoid" => Ok(ActivationFunction::Sigmoid),
+            "relu" => Ok(ActivationFunction::Relu),
+            "tanh" => Ok(ActivationFunction::Tanh),
+            "swish" => Ok(ActivationFunction::Swish),
+            "softplus" => Ok(ActivationFunction::Softplus),
+            "linear" => Ok(ActivationFunction::Linear),
+            _ => Err(format!("Fonction d'activation inconnue : {}", name)),
+        }
+    }
+}
+
+impl TryFrom<String> for ActivationFunction {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        name.parse()
+    }
+}
+
+impl From<ActivationFunction> for String {
+    fn from(activation_function: ActivationFunction) -> Self {
+        activation_function.name().to_string()
+    }
+}
@@ -1,17 +1,25 @@
 use crate::layer::Layer;
+use crate::loss::LossFunction;
 use crate::neural_network::NeuralNetwork;
 use crate::neuron::Neuron;
+use rand::Rng;
 
 pub trait NeuronExt {
     fn calculate_gradient(&self, input: &[f32], target: f32, output: f32) -> f32;
     fn update_weights(&mut self, inputs: &[f32], gradient: f32, learning_rate: f32);
-    fn calculate_derivative(&self, value: f32) -> f32;
+    fn calculate_derivative(&self, pre_activation: f32, output: f32) -> f32;
 }
 
 impl NeuronExt for Neuron {
-    fn calculate_gradient(&self, _input: &[f32], target: f32, output: f32) -> f32 {
+    fn calculate_gradient(&self, input: &[f32], target: f32, output: f32) -> f32 {
         // For output neurons: gradient = (target - output) * derivative(output)
-        let derivative = self.calculate_derivative(output);
+        let pre_activation = input
+            .iter()
+            .zip(&self.weights)
+            .map(|(x, w)| x * w)
+            .sum::<f32>()
+            + self.effective_bias();
+        let derivative = self.calculate_derivative(pre_activation, output);
         (target - output) * derivative
     }
 
@@ -21,116 +29,702 @@ impl NeuronExt for Neuron {
                 self.weights[i] += learning_rate * gradient * input;
             }
         }
-        self.bias += learning_rate * gradient;
+        if self.use_bias {
+            self.bias += learning_rate * gradient;
+        }
     }
 
-    fn calculate_derivative(&self, value: f32) -> f32 {
-        match self.activation_function.as_str() {
-            "sigmoid" => {
-                // Derivative of sigmoid: sigmoid(x) * (1 - sigmoid(x))
-                value * (1.0 - value)
-            }
-            "relu" => {
-                // Derivative of ReLU: 1 if x > 0, 0 otherwise
-                if value > 0.0 { 1.0 } else { 0.0 }
-            }
-            // Default to linear derivative
-            _ => 1.0,
-        }
+    fn calculate_derivative(&self, pre_activation: f32, output: f32) -> f32 {
+        self.activation_function.derivative(pre_activation, output)
     }
 }
 
 pub trait LayerExt {
     fn forward_with_cache(&self, inputs: &[f32]) -> (Vec<f32>, Vec<f32>);
-    fn backward(&mut self, inputs: &[f32], gradients: &[f32], learning_rate: f32) -> Vec<f32>;
+    fn forward_with_cache_checked(&self, inputs: &[f32]) -> Result<(Vec<f32>, Vec<f32>), String>;
+    fn forward_into(&self, inputs: &[f32], outputs: &mut Vec<f32>, pre_activations: &mut Vec<f32>);
+    fn propagate_gradients(&self, gradients: &[f32], input_size: usize) -> Vec<f32>;
 }
 
 impl LayerExt for Layer {
     fn forward_with_cache(&self, inputs: &[f32]) -> (Vec<f32>, Vec<f32>) {
         let mut outputs = Vec::with_capacity(self.neurons.len());
         let mut pre_activations = Vec::with_capacity(self.neurons.len());
+        self.forward_into(inputs, &mut outputs, &mut pre_activations);
+
+        (outputs, pre_activations)
+    }
+
+    fn forward_with_cache_checked(&self, inputs: &[f32]) -> Result<(Vec<f32>, Vec<f32>), String> {
+        if let Some(neuron) = self
+            .neurons
+            .iter()
+            .find(|neuron| neuron.weights.len() != inputs.len())
+        {
+            return Err(format!(
+                "Le neurone {} de la couche {} a {} poids pour {} entrées",
+                neuron.id,
+                self.id,
+                neuron.weights.len(),
+                inputs.len()
+            ));
+        }
+
+        Ok(self.forward_with_cache(inputs))
+    }
+
+    fn forward_into(&self, inputs: &[f32], outputs: &mut Vec<f32>, pre_activations: &mut Vec<f32>) {
+        outputs.clear();
+        pre_activations.clear();
 
         for neuron in &self.neurons {
+            // `zip` would silently truncate to the shorter of the two, e.g. for a corrupted model.
+            debug_assert_eq!(
+                inputs.len(),
+                neuron.weights.len(),
+                "le neurone {} de la couche {} n'a pas le bon nombre de poids",
+                neuron.id,
+                self.id
+            );
             let weighted_sum: f32 = inputs
                 .iter()
                 .zip(&neuron.weights)
                 .map(|(x, w)| x * w)
                 .sum::<f32>()
-                + neuron.bias;
+                + neuron.effective_bias();
 
             pre_activations.push(weighted_sum);
             outputs.push(neuron.apply_activation_function(weighted_sum));
         }
+    }
 
-        (outputs, pre_activations)
+    fn propagate_gradients(&self, gradients: &[f32], input_size: usize) -> Vec<f32> {
+        let mut prev_layer_gradients = vec![0.0; input_size];
+        for (neuron, &gradient) in self.neurons.iter().zip(gradients) {
+            for (prev_gradient, &weight) in prev_layer_gradients.iter_mut().zip(&neuron.weights) {
+                *prev_gradient += gradient * weight;
+            }
+        }
+
+        prev_layer_gradients
     }
+}
+
+/// Layer-output vectors reused across forward passes.
+///
+/// `layer_outputs[0]` holds a copy of the inputs and `layer_outputs[i + 1]` the outputs
+/// of layer `i`, while `pre_activations[i]` holds the weighted sums of layer `i` before
+/// its activation. The vectors are cleared and refilled, never reallocated once they have
+/// reached the size of their layer. The backward passes also leave the parameter gradients
+/// of the last sample in `parameter_gradients`, in `NeuralNetwork::get_parameters` order.
+#[derive(Debug, Clone, Default)]
+pub struct ForwardBuffers {
+    pub layer_outputs: Vec<Vec<f32>>,
+    pub pre_activations: Vec<Vec<f32>>,
+    pub parameter_gradients: Vec<f32>,
+}
 
-    fn backward(&mut self, inputs: &[f32], gradients: &[f32], learning_rate: f32) -> Vec<f32> {
-        let mut prev_layer_gradients = vec![0.0; inputs.len()];
-        for (neuron_idx, neuron) in self.neurons.iter_mut().enumerate() {
-            let gradient = gradients[neuron_idx];
+impl ForwardBuffers {
+    pub fn for_network(network: &NeuralNetwork) -> Self {
+        let input_size = network
+            .layers
+            .first()
+            .and_then(|layer| layer.neurons.first())
+            .map_or(0, |neuron| neuron.weights.len());
 
-            neuron.update_weights(inputs, gradient, learning_rate);
+        let mut layer_outputs = vec![Vec::with_capacity(input_size)];
+        layer_outputs.extend(
+            network
+                .layers
+                .iter()
+                .map(|layer| Vec::with_capacity(layer.neurons.len())),
+        );
 
-            for (input_idx, &weight) in neuron.weights.iter().enumerate() {
-                if input_idx < prev_layer_gradients.len() {
-                    prev_layer_gradients[input_idx] += gradient * weight;
-                }
-            }
+        let pre_activations = network
+            .layers
+            .iter()
+            .map(|layer| Vec::with_capacity(layer.neurons.len()))
+            .collect();
+
+        ForwardBuffers {
+            layer_outputs,
+            pre_activations,
+            parameter_gradients: Vec::with_capacity(
+                network
+                    .layers
+                    .iter()
+                    .flat_map(|layer| &layer.neurons)
+                    .map(|neuron| neuron.weights.len() + 1)
+                    .sum(),
+            ),
         }
+    }
+}
 
-        prev_layer_gradients
+/// An L1 penalty `lambda * sum(|activation|)` on the outputs of the layer whose id is
+/// `layer_id`, added to the loss to encourage sparse hidden representations. Its subgradient
+/// `lambda * sign(activation)` is added to that layer's output gradients.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct L1ActivationPenalty {
+    pub layer_id: u32,
+    pub lambda: f32,
+}
+
+impl L1ActivationPenalty {
+    fn applies_to(&self, layer: &Layer) -> bool {
+        layer.id == self.layer_id && self.lambda != 0.0
+    }
+
+    fn value(&self, activations: &[f32]) -> f32 {
+        self.lambda * activations.iter().map(|a| a.abs()).sum::<f32>()
+    }
+
+    // sign(0) is 0, so inactive ReLU units are left alone.
+    fn subgradient(&self, activation: f32) -> f32 {
+        if activation > 0.0 {
+            self.lambda
+        } else if activation < 0.0 {
+            -self.lambda
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Parameter gradients dL/dθ of a network, laid out as in `NeuralNetwork::get_parameters`
+/// (the weights then the bias of every neuron, layer by layer).
+///
+/// Returned by `NetworkExt::backward_no_update` and consumed by `NetworkExt::apply_gradients`,
+/// so several samples can be accumulated before a single update.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Gradients {
+    pub values: Vec<f32>,
+}
+
+impl Gradients {
+    /// Adds `other` element-wise. An empty accumulator takes the shape of `other`.
+    pub fn accumulate(&mut self, other: &Gradients) {
+        if self.values.is_empty() {
+            self.values = other.values.clone();
+            return;
+        }
+        for (value, &other) in self.values.iter_mut().zip(&other.values) {
+            *value += other;
+        }
+    }
+
+    /// Multiplies every gradient by `factor`, e.g. `1 / batch_size` to average an accumulation.
+    pub fn scale(&mut self, factor: f32) {
+        for value in &mut self.values {
+            *value *= factor;
+        }
+    }
+
+    /// Sets every gradient back to zero, keeping the shape.
+    pub fn reset(&mut self) {
+        self.values.fill(0.0);
+    }
+
+    /// Euclidean norm of all the gradients.
+    pub fn norm(&self) -> f32 {
+        self.values.iter().map(|g| g * g).sum::<f32>().sqrt()
     }
 }
 
 pub trait NetworkExt {
     fn forward_with_cache(&self, inputs: &[f32]) -> Vec<Vec<f32>>;
+    fn forward_with_buffers(&self, inputs: &[f32], buffers: &mut ForwardBuffers);
+    fn forward_with_dropout<R: Rng>(
+        &self,
+        inputs: &[f32],
+        dropout_rate: f32,
+        rng: &mut R,
+        buffers: &mut ForwardBuffers,
+    );
     fn backward(&mut self, inputs: &[f32], targets: &[f32], learning_rate: f32) -> f32;
+    fn backward_with_buffers(
+        &mut self,
+        inputs: &[f32],
+        targets: &[f32],
+        learning_rate: f32,
+        buffers: &mut ForwardBuffers,
+    ) -> f32;
+    fn backward_with_loss(
+        &mut self,
+        inputs: &[f32],
+        targets: &[f32],
+        learning_rate: f32,
+        loss_fn: LossFunction,
+        buffers: &mut ForwardBuffers,
+    ) -> f32;
+    fn backward_with_gradient_norm(
+        &mut self,
+        inputs: &[f32],
+        targets: &[f32],
+        learning_rate: f32,
+        loss_fn: LossFunction,
+        buffers: &mut ForwardBuffers,
+    ) -> (f32, f32);
+    fn backward_regularized(
+        &mut self,
+        inputs: &[f32],
+        targets: &[f32],
+        learning_rate: f32,
+        loss_fn: LossFunction,
+        penalty: Option<L1ActivationPenalty>,
+        buffers: &mut ForwardBuffers,
+    ) -> (f32, f32);
+    fn backward_no_update(
+        &self,
+        inputs: &[f32],
+        targets: &[f32],
+        loss_fn: LossFunction,
+    ) -> (f32, Gradients);
+    fn apply_gradients(&mut self, gradients: &Gradients, learning_rate: f32);
+    fn output_layer_parameter_count(&self) -> usize;
+    fn input_gradient(&self, inputs: &[f32]) -> Vec<f32>;
+    fn feature_gradient(&self, inputs: &[f32], feature_index: usize) -> f32;
+    fn jacobian(&self, inputs: &[f32]) -> Vec<Vec<f32>>;
+    fn parameter_gradients(&self, inputs: &[f32], targets: &[f32]) -> (f32, Vec<f32>);
+    fn parameter_gradients_with_loss(
+        &self,
+        inputs: &[f32],
+        targets: &[f32],
+        loss_fn: LossFunction,
+    ) -> (f32, Vec<f32>);
+    fn parameter_gradients_regularized(
+        &self,
+        inputs: &[f32],
+        targets: &[f32],
+        loss_fn: LossFunction,
+        penalty: Option<L1ActivationPenalty>,
+    ) -> (f32, Vec<f32>);
+    fn compute_loss(&self, inputs: &[Vec<f32>], targets: &[Vec<f32>], loss_fn: LossFunction)
+    -> f32;
 }
 
 impl NetworkExt for NeuralNetwork {
     fn forward_with_cache(&self, inputs: &[f32]) -> Vec<Vec<f32>> {
-        let mut layer_outputs = Vec::new();
-        let mut current_inputs = inputs.to_vec();
-        layer_outputs.push(current_inputs.clone());
+        let mut buffers = ForwardBuffers::default();
+        self.forward_with_buffers(inputs, &mut buffers);
+        buffers.layer_outputs
+    }
+
+    fn forward_with_buffers(&self, inputs: &[f32], buffers: &mut ForwardBuffers) {
+        let layer_outputs = &mut buffers.layer_outputs;
+        layer_outputs.resize_with(self.layers.len() + 1, Vec::new);
+        buffers
+            .pre_activations
+            .resize_with(self.layers.len(), Vec::new);
 
-        for layer in &self.layers {
-            let (layer_output, _) = layer.forward_with_cache(&current_inputs);
-            layer_outputs.push(layer_output.clone());
-            current_inputs = layer_output;
+        layer_outputs[0].clear();
+        layer_outputs[0].extend_from_slice(inputs);
+
+        for (layer_idx, layer) in self.layers.iter().enumerate() {
+            let (previous, next) = layer_outputs.split_at_mut(layer_idx + 1);
+            layer.forward_into(
+                &previous[layer_idx],
+                &mut next[0],
+                &mut buffers.pre_activations[layer_idx],
+            );
         }
+    }
+
+    // Inverted dropout on the outputs of every hidden layer: each one is zeroed with
+    // probability `dropout_rate` and the others are scaled by `1 / (1 - dropout_rate)`.
+    // The output layer is never dropped, so its pre-activations are the dropped-out logits.
+    fn forward_with_dropout<R: Rng>(
+        &self,
+        inputs: &[f32],
+        dropout_rate: f32,
+        rng: &mut R,
+        buffers: &mut ForwardBuffers,
+    ) {
+        let layer_outputs = &mut buffers.layer_outputs;
+        layer_outputs.resize_with(self.layers.len() + 1, Vec::new);
+        buffers
+            .pre_activations
+            .resize_with(self.layers.len(), Vec::new);
+
+        layer_outputs[0].clear();
+        layer_outputs[0].extend_from_slice(inputs);
 
-        layer_outputs
+        let keep_scale = 1.0 / (1.0 - dropout_rate);
+        for (layer_idx, layer) in self.layers.iter().enumerate() {
+            let (previous, next) = layer_outputs.split_at_mut(layer_idx + 1);
+            layer.forward_into(
+                &previous[layer_idx],
+                &mut next[0],
+                &mut buffers.pre_activations[layer_idx],
+            );
+
+            if layer_idx + 1 < self.layers.len() && dropout_rate > 0.0 {
+                for output in &mut next[0] {
+                    *output = if rng.random::<f32>() < dropout_rate {
+                        0.0
+                    } else {
+                        *output * keep_scale
+                    };
+                }
+            }
+        }
     }
 
     fn backward(&mut self, inputs: &[f32], targets: &[f32], learning_rate: f32) -> f32 {
-        let layer_outputs = self.forward_with_cache(inputs);
+        self.backward_with_buffers(
+            inputs,
+            targets,
+            learning_rate,
+            &mut ForwardBuffers::default(),
+        )
+    }
+
+    fn backward_with_buffers(
+        &mut self,
+        inputs: &[f32],
+        targets: &[f32],
+        learning_rate: f32,
+        buffers: &mut ForwardBuffers,
+    ) -> f32 {
+        self.backward_with_loss(
+            inputs,
+            targets,
+            learning_rate,
+            LossFunction::MeanSquaredError,
+            buffers,
+        )
+    }
 
-        let network_output = layer_outputs.last().unwrap();
-        let mut loss = 0.0;
-        for (output, target) in network_output.iter().zip(targets) {
-            loss += 0.5 * (target - output).powi(2);
+    fn backward_with_loss(
+        &mut self,
+        inputs: &[f32],
+        targets: &[f32],
+        learning_rate: f32,
+        loss_fn: LossFunction,
+        buffers: &mut ForwardBuffers,
+    ) -> f32 {
+        self.backward_with_gradient_norm(inputs, targets, learning_rate, loss_fn, buffers)
+            .0
+    }
+
+    fn backward_with_gradient_norm(
+        &mut self,
+        inputs: &[f32],
+        targets: &[f32],
+        learning_rate: f32,
+        loss_fn: LossFunction,
+        buffers: &mut ForwardBuffers,
+    ) -> (f32, f32) {
+        self.backward_regularized(inputs, targets, learning_rate, loss_fn, None, buffers)
+    }
+
+    fn backward_regularized(
+        &mut self,
+        inputs: &[f32],
+        targets: &[f32],
+        learning_rate: f32,
+        loss_fn: LossFunction,
+        penalty: Option<L1ActivationPenalty>,
+        buffers: &mut ForwardBuffers,
+    ) -> (f32, f32) {
+        let loss = parameter_gradients_into(self, inputs, targets, loss_fn, penalty, buffers);
+        let gradients = &buffers.parameter_gradients;
+
+        let output_parameter_count = self.output_layer_parameter_count();
+        let gradient_norm = gradients[gradients.len() - output_parameter_count..]
+            .iter()
+            .map(|g| g * g)
+            .sum::<f32>()
+            .sqrt();
+
+        apply_gradient_values(self, gradients, learning_rate);
+        (loss, gradient_norm)
+    }
+
+    fn backward_no_update(
+        &self,
+        inputs: &[f32],
+        targets: &[f32],
+        loss_fn: LossFunction,
+    ) -> (f32, Gradients) {
+        let (loss, values) = self.parameter_gradients_with_loss(inputs, targets, loss_fn);
+        (loss, Gradients { values })
+    }
+
+    fn apply_gradients(&mut self, gradients: &Gradients, learning_rate: f32) {
+        apply_gradient_values(self, &gradients.values, learning_rate);
+    }
+
+    fn output_layer_parameter_count(&self) -> usize {
+        self.layers.last().map_or(0, |layer| {
+            layer
+                .neurons
+                .iter()
+                .map(|neuron| neuron.weights.len() + 1)
+                .sum()
+        })
+    }
+
+    fn input_gradient(&self, inputs: &[f32]) -> Vec<f32> {
+        let mut buffers = ForwardBuffers::default();
+        self.forward_with_buffers(inputs, &mut buffers);
+
+        // Seed the output layer with a gradient of 1.0, then apply the chain rule
+        // down to the inputs without touching any weight.
+        let output_count = self.layers.last().unwrap().neurons.len();
+        propagate_to_inputs(self, &buffers, &vec![1.0; output_count])
+    }
+
+    fn feature_gradient(&self, inputs: &[f32], feature_index: usize) -> f32 {
+        // Panics like an out-of-bounds index when `feature_index >= inputs.len()`.
+        self.input_gradient(inputs)[feature_index]
+    }
+
+    fn jacobian(&self, inputs: &[f32]) -> Vec<Vec<f32>> {
+        let mut buffers = ForwardBuffers::default();
+        self.forward_with_buffers(inputs, &mut buffers);
+
+        // One backward pass per output, seeded with a unit gradient on that output only.
+        let output_count = self.layers.last().unwrap().neurons.len();
+        (0..output_count)
+            .map(|output_idx| {
+                let mut seed = vec![0.0; output_count];
+                seed[output_idx] = 1.0;
+                propagate_to_inputs(self, &buffers, &seed)
+            })
+            .collect()
+    }
+
+    fn parameter_gradients(&self, inputs: &[f32], targets: &[f32]) -> (f32, Vec<f32>) {
+        self.parameter_gradients_with_loss(inputs, targets, LossFunction::MeanSquaredError)
+    }
+
+    fn parameter_gradients_with_loss(
+        &self,
+        inputs: &[f32],
+        targets: &[f32],
+        loss_fn: LossFunction,
+    ) -> (f32, Vec<f32>) {
+        self.parameter_gradients_regularized(inputs, targets, loss_fn, None)
+    }
+
+    fn parameter_gradients_regularized(
+        &self,
+        inputs: &[f32],
+        targets: &[f32],
+        loss_fn: LossFunction,
+        penalty: Option<L1ActivationPenalty>,
+    ) -> (f32, Vec<f32>) {
+        let mut buffers = ForwardBuffers::default();
+        let loss = parameter_gradients_into(self, inputs, targets, loss_fn, penalty, &mut buffers);
+        (loss, buffers.parameter_gradients)
+    }
+
+    fn compute_loss(
+        &self,
+        inputs: &[Vec<f32>],
+        targets: &[Vec<f32>],
+        loss_fn: LossFunction,
+    ) -> f32 {
+        if inputs.is_empty() {
+            return 0.0;
         }
 
-        let mut next_gradients = Vec::with_capacity(network_output.len());
+        // Mean over samples of the loss summed over outputs, without touching any weight.
+        let mut buffers = ForwardBuffers::for_network(self);
+        let mut total_loss = 0.0;
+        for (input, target) in inputs.iter().zip(targets) {
+            self.forward_with_buffers(input, &mut buffers);
+            total_loss += buffers
+                .layer_outputs
+                .last()
+                .unwrap()
+                .iter()
+                .zip(target)
+                .map(|(&output, &target)| loss_fn.loss(output, target))
+                .sum::<f32>();
+        }
 
-        for (i, (&output, &target)) in network_output.iter().zip(targets).enumerate() {
-            let output_neuron = &self.layers.last().unwrap().neurons[i];
-            let deriv = output_neuron.calculate_derivative(output);
-            next_gradients.push((target - output) * deriv);
+        total_loss / inputs.len() as f32
+    }
+}
+
+// dL/dθ for one sample, written to `buffers.parameter_gradients` in
+// `NeuralNetwork::get_parameters` order, from a forward pass into `buffers`. Returns the
+// (penalized) loss. Nothing is updated, so every layer sees the original weights.
+fn parameter_gradients_into(
+    network: &NeuralNetwork,
+    inputs: &[f32],
+    targets: &[f32],
+    loss_fn: LossFunction,
+    penalty: Option<L1ActivationPenalty>,
+    buffers: &mut ForwardBuffers,
+) -> f32 {
+    network.forward_with_buffers(inputs, buffers);
+    let layer_outputs = &buffers.layer_outputs;
+    let pre_activations = &buffers.pre_activations;
+    let network_output = layer_outputs.last().unwrap();
+
+    let mut loss = 0.0;
+    for (&output, &target) in network_output.iter().zip(targets) {
+        loss += loss_fn.loss(output, target);
+    }
+    loss += activation_penalty(network, layer_outputs, penalty);
+
+    // Each layer's gradients are written at its offset, so the buffer is filled from the
+    // output layer down without any per-layer allocation.
+    let parameter_count = |layer: &Layer| -> usize {
+        layer
+            .neurons
+            .iter()
+            .map(|neuron| neuron.weights.len() + 1)
+            .sum()
+    };
+    let gradients = &mut buffers.parameter_gradients;
+    gradients.clear();
+    gradients.resize(network.layers.iter().map(parameter_count).sum(), 0.0);
+
+    // dL/dz for each output neuron, then the chain rule down the layers.
+    let mut deltas: Vec<f32> = network
+        .layers
+        .last()
+        .unwrap()
+        .neurons
+        .iter()
+        .zip(pre_activations.last().unwrap())
+        .zip(network_output.iter().zip(targets))
+        .map(|((neuron, &pre_activation), (&output, &target))| {
+            loss_fn.gradient(output, target) * neuron.calculate_derivative(pre_activation, output)
+        })
+        .collect();
+
+    let mut offset = gradients.len();
+    for layer_idx in (0..network.layers.len()).rev() {
+        let layer_inputs = &layer_outputs[layer_idx];
+        let layer = &network.layers[layer_idx];
+        offset -= parameter_count(layer);
+
+        let mut position = offset;
+        for (neuron, &delta) in layer.neurons.iter().zip(&deltas) {
+            for (gradient, &input) in gradients[position..]
+                .iter_mut()
+                .zip(layer_inputs)
+                .take(neuron.weights.len())
+            {
+                *gradient = delta * input;
+            }
+            position += neuron.weights.len();
+            gradients[position] = if neuron.use_bias { delta } else { 0.0 };
+            position += 1;
+        }
+
+        if layer_idx > 0 {
+            deltas = layer.propagate_gradients(&deltas, layer_inputs.len());
+            if let Some(penalty) = penalty
+                && penalty.applies_to(&network.layers[layer_idx - 1])
+            {
+                for (delta, &activation) in deltas.iter_mut().zip(layer_inputs) {
+                    *delta += penalty.subgradient(activation);
+                }
+            }
+            for (delta, (neuron, (&pre_activation, &output))) in deltas.iter_mut().zip(
+                network.layers[layer_idx - 1]
+                    .neurons
+                    .iter()
+                    .zip(pre_activations[layer_idx - 1].iter().zip(layer_inputs)),
+            ) {
+                *delta *= neuron.calculate_derivative(pre_activation, output);
+            }
         }
+    }
 
-        for layer_idx in (0..self.layers.len()).rev() {
-            let layer_inputs = if layer_idx == 0 {
-                inputs.to_vec()
-            } else {
-                layer_outputs[layer_idx].clone()
-            };
+    loss
+}
 
-            next_gradients =
-                self.layers[layer_idx].backward(&layer_inputs, &next_gradients, learning_rate);
+// θ -= learning_rate * dL/dθ, with `gradients` in `NeuralNetwork::get_parameters` order.
+fn apply_gradient_values(network: &mut NeuralNetwork, gradients: &[f32], learning_rate: f32) {
+    let mut values = gradients.iter();
+    for neuron in network
+        .layers
+        .iter_mut()
+        .flat_map(|layer| &mut layer.neurons)
+    {
+        for (weight, gradient) in neuron.weights.iter_mut().zip(values.by_ref()) {
+            *weight -= learning_rate * gradient;
         }
+        if let Some(gradient) = values.next()
+            && neuron.use_bias
+        {
+            neuron.bias -= learning_rate * gradient;
+        }
+    }
+}
+
+// The L1 penalty of the forward pass cached in `layer_outputs`; `layer_outputs[i + 1]` holds
+// the activations of layer `i`.
+fn activation_penalty(
+    network: &NeuralNetwork,
+    layer_outputs: &[Vec<f32>],
+    penalty: Option<L1ActivationPenalty>,
+) -> f32 {
+    let Some(penalty) = penalty else {
+        return 0.0;
+    };
+
+    network
+        .layers
+        .iter()
+        .zip(&layer_outputs[1..])
+        .filter(|(layer, _)| penalty.applies_to(layer))
+        .map(|(_, activations)| penalty.value(activations))
+        .sum()
+}
 
-        loss
+// Backpropagates `output_seed` (dOutput for each output neuron) from the cached forward pass
+// in `buffers` down to the network inputs, without touching any weight.
+fn propagate_to_inputs(
+    network: &NeuralNetwork,
+    buffers: &ForwardBuffers,
+    output_seed: &[f32],
+) -> Vec<f32> {
+    let layer_outputs = &buffers.layer_outputs;
+    let pre_activations = &buffers.pre_activations;
+
+    let mut gradients: Vec<f32> = network
+        .layers
+        .last()
+        .unwrap()
+        .neurons
+        .iter()
+        .zip(
+            pre_activations
+                .last()
+                .unwrap()
+                .iter()
+                .zip(layer_outputs.last().unwrap()),
+        )
+        .zip(output_seed)
+        .map(|((neuron, (&pre_activation, &output)), seed)| {
+            seed * neuron.calculate_derivative(pre_activation, output)
+        })
+        .collect();
+
+    for layer_idx in (0..network.layers.len()).rev() {
+        let input_size = layer_outputs[layer_idx].len();
+        gradients = network.layers[layer_idx].propagate_gradients(&gradients, input_size);
+
+        if layer_idx > 0 {
+            for (gradient, (neuron, (&pre_activation, &output))) in gradients.iter_mut().zip(
+                network.layers[layer_idx - 1].neurons.iter().zip(
+                    pre_activations[layer_idx - 1]
+                        .iter()
+                        .zip(&layer_outputs[layer_idx]),
+                ),
+            ) {
+                *gradient *= neuron.calculate_derivative(pre_activation, output);
+            }
+        }
     }
+
+    gradients
 }
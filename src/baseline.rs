@@ -0,0 +1,63 @@
+use crate::back_propagation::NetworkExt;
+use crate::dataset_loader::{
+    SimplifiedWeatherDataPoint, WeatherInput, prepare_inputs, prepare_outputs,
+};
+use crate::neural_network::NeuralNetwork;
+use crate::trainer::BinaryTrainer;
+
+/// Classifies `query` by majority vote among its `k` nearest neighbours in `train`.
+///
+/// Distances are Euclidean over the four features, so `train` and `query` are expected to
+/// be normalized with the same parameters. A tie between the two classes counts as clear.
+pub fn knn_classify(train: &[SimplifiedWeatherDataPoint], query: &WeatherInput, k: usize) -> bool {
+    let query = query.to_vec();
+    let mut neighbours: Vec<(f32, bool)> = train
+        .iter()
+        .map(|data_point| {
+            let distance = data_point
+                .input
+                .to_vec()
+                .iter()
+                .zip(&query)
+                .map(|(a, b)| (a - b).powi(2))
+                .sum::<f32>();
+            (distance, data_point.output)
+        })
+        .collect();
+
+    if neighbours.is_empty() {
+        return false;
+    }
+
+    let k = k.clamp(1, neighbours.len());
+    neighbours.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let precipitation_votes = neighbours
+        .iter()
+        .take(k)
+        .filter(|(_, output)| *output)
+        .count();
+    precipitation_votes * 2 > k
+}
+
+/// Trains a logistic regression, i.e. a single 4 -> 1 sigmoid neuron, on `data`.
+///
+/// Every epoch runs plain per-sample gradient descent over `data` in order, without
+/// early stopping or logging.
+pub fn train_logistic(
+    data: &[SimplifiedWeatherDataPoint],
+    learning_rate: f32,
+    epochs: usize,
+) -> NeuralNetwork {
+    let mut network = BinaryTrainer::new(learning_rate, epochs, 1).create_weather_network(4, &[]);
+    let inputs = prepare_inputs(data);
+    let targets = prepare_outputs(data);
+
+    for _ in 0..epochs {
+        for (input, target) in inputs.iter().zip(&targets) {
+            network.backward(input, target, learning_rate);
+        }
+    }
+
+    network
+}
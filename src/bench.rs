@@ -0,0 +1,25 @@
+use crate::neural_network::NeuralNetwork;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// Average latency of one `NeuralNetwork::activate` call on `sample_input`, over `iterations`
+/// forward passes, e.g. to size a deployment.
+///
+/// Returns `Duration::ZERO` when `iterations` is 0. A single pass is too short to time
+/// reliably, so use a few thousand iterations for a meaningful figure.
+pub fn benchmark_inference(
+    network: &NeuralNetwork,
+    sample_input: &[f32],
+    iterations: usize,
+) -> Duration {
+    if iterations == 0 {
+        return Duration::ZERO;
+    }
+
+    let start = Instant::now();
+    for _ in 0..iterations {
+        black_box(network.activate(black_box(sample_input)));
+    }
+
+    start.elapsed().div_f64(iterations as f64)
+}
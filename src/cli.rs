@@ -0,0 +1,306 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::dataset_loader::{
+    self, SimplifiedWeatherDataPoint, WeatherInput, normalize_with_params,
+};
+use crate::metrics::InclusiveBound;
+use crate::pickle::{self, SaveOptions};
+use crate::trainer::{BinaryTrainer, TrainingConfig};
+use crate::weather_model::WeatherModel;
+
+const DEFAULT_MODEL_PATH: &str = "weather_model.json";
+
+/// Runs the subcommand named by `args[0]` and returns the text to print.
+///
+/// `args` excludes the program name, e.g.
+/// `["predict", "--temp", "22", "--pressure", "1016", "--altitude", "300", "--humidity", "70"]`.
+pub fn run(args: &[String]) -> Result<String, Box<dyn Error>> {
+    let (command, rest) = args.split_first().ok_or("Aucune sous-commande fournie")?;
+    let flags = parse_flags(rest)?;
+
+    match command.as_str() {
+        "predict" => {
+            let input = WeatherInput {
+                temp: parse_flag(&flags, "temp")?,
+                pressure: parse_flag(&flags, "pressure")?,
+                altitude: parse_flag(&flags, "altitude")?,
+                humidity: parse_flag(&flags, "humidity")?,
+            };
+            input
+                .validate()
+                .map_err(|violations| format!("Entrée invalide : {}", violations.join(", ")))?;
+            let model_path = flags.get("model").copied().unwrap_or(DEFAULT_MODEL_PATH);
+            predict(&input, model_path)
+        }
+        "predict-batch" => {
+            let input_path = required_flag(&flags, "input")?;
+            let output_path = required_flag(&flags, "output")?;
+            let model_path = flags.get("model").copied().unwrap_or(DEFAULT_MODEL_PATH);
+            let count = predict_batch(input_path, output_path, model_path)?;
+            Ok(format!(
+                "{} prédictions écrites dans {}",
+                count, output_path
+            ))
+        }
+        "train" => {
+            let trainer = BinaryTrainer::new(
+                parse_flag_or(&flags, "lr", 0.05)?,
+                parse_flag_or(&flags, "epochs", 1000)?,
+                parse_flag_or(&flags, "batch-size", 20)?,
+            );
+            trainer.validate()?;
+            let hidden_sizes = match flags.get("hidden") {
+                Some(hidden) => parse_hidden_sizes(hidden)?,
+                None => vec![8, 4],
+            };
+            let train_path = flags
+                .get("train-data")
+                .copied()
+                .unwrap_or("weather-train-dataset.json");
+            let test_path = flags
+                .get("test-data")
+                .copied()
+                .unwrap_or("weather-test-dataset.json");
+            let out_path = flags.get("out").copied().unwrap_or(DEFAULT_MODEL_PATH);
+            let pre_normalized = flags.contains_key("pre-normalized");
+
+            let accuracy = train_with_normalization(
+                &trainer,
+                &hidden_sizes,
+                train_path,
+                test_path,
+                out_path,
+                pre_normalized,
+            )?;
+            Ok(format!(
+                "Précision de validation : {:.2}%\nModèle sauvegardé dans {}",
+                accuracy * 100.0,
+                out_path
+            ))
+        }
+        other => Err(format!("Sous-commande inconnue : {}", other).into()),
+    }
+}
+
+/// Loads the model at `model_path` and describes its prediction for the raw `input`.
+pub fn predict<P: AsRef<Path>>(
+    input: &WeatherInput,
+    model_path: P,
+) -> Result<String, Box<dyn Error>> {
+    let model = WeatherModel::from(pickle::load_saved_model(model_path)?);
+    let probability = model.predict_proba(input);
+
+    Ok(format!(
+        "Probabilité de précipitations : {:.4}\nPrédiction binaire : {}",
+        probability,
+        verdict(InclusiveBound::AtLeast.is_positive(probability, 0.5))
+    ))
+}
+
+/// Scores every input of `input_path` and writes them as CSV rows to `output_path`.
+///
+/// The input is either a JSON array of `WeatherInput`s or, for a `.csv` file,
+/// `temp,pressure,altitude,humidity` rows with an optional header. Each output row is
+/// `temp,pressure,altitude,humidity,probability,prediction`, the prediction being 1 for
+/// precipitation and 0 otherwise. Returns the number of rows written.
+pub fn predict_batch<P: AsRef<Path>, Q: AsRef<Path>, M: AsRef<Path>>(
+    input_path: P,
+    output_path: Q,
+    model_path: M,
+) -> Result<usize, Box<dyn Error>> {
+    let inputs = read_inputs(input_path.as_ref())?;
+    for (input_idx, input) in inputs.iter().enumerate() {
+        input.validate().map_err(|violations| {
+            format!(
+                "Entrée {} invalide : {}",
+                input_idx + 1,
+                violations.join(", ")
+            )
+        })?;
+    }
+    // Scored like `predict`, so a calibrated temperature applies to every row.
+    let model = WeatherModel::from(pickle::load_saved_model(model_path)?);
+
+    let mut csv = String::from("temp,pressure,altitude,humidity,probability,prediction\n");
+    for input in &inputs {
+        let probability = model.predict_proba(input);
+
+        writeln!(
+            csv,
+            "{},{},{},{},{:.4},{}",
+            input.temp,
+            input.pressure,
+            input.altitude,
+            input.humidity,
+            probability,
+            u8::from(InclusiveBound::AtLeast.is_positive(probability, 0.5))
+        )?;
+    }
+
+    fs::write(output_path, csv)?;
+    Ok(inputs.len())
+}
+
+/// Trains a `4 -> hidden_sizes -> 1` network on `train_path`, validating on `test_path`,
+/// and saves it with its normalization to `out_path`. Returns the best validation accuracy.
+///
+/// The test set is normalized with the parameters computed on the training set.
+pub fn train<P: AsRef<Path>, Q: AsRef<Path>, O: AsRef<Path>>(
+    trainer: &BinaryTrainer,
+    hidden_sizes: &[usize],
+    train_path: P,
+    test_path: Q,
+    out_path: O,
+) -> Result<f32, Box<dyn Error>> {
+    train_with_normalization(
+        trainer,
+        hidden_sizes,
+        train_path,
+        test_path,
+        out_path,
+        false,
+    )
+}
+
+/// Like `train`, but with `pre_normalized` set the datasets are used as they are and the
+/// model is saved with the identity normalization.
+pub fn train_with_normalization<P: AsRef<Path>, Q: AsRef<Path>, O: AsRef<Path>>(
+    trainer: &BinaryTrainer,
+    hidden_sizes: &[usize],
+    train_path: P,
+    test_path: Q,
+    out_path: O,
+    pre_normalized: bool,
+) -> Result<f32, Box<dyn Error>> {
+    let train_data = dataset_loader::simplify_forecasts(&dataset_loader::load_dataset(train_path)?);
+    let test_data = dataset_loader::simplify_forecasts(&dataset_loader::load_dataset(test_path)?);
+
+    let (normalized_train, normalization_params) =
+        dataset_loader::normalize_inputs_or_skip(&train_data, pre_normalized);
+    let normalized_test: Vec<SimplifiedWeatherDataPoint> = test_data
+        .iter()
+        .map(|data_point| SimplifiedWeatherDataPoint {
+            input: normalize_with_params(&data_point.input, &normalization_params),
+            output: data_point.output,
+        })
+        .collect();
+
+    let training_config = TrainingConfig::new(trainer.clone(), 4, hidden_sizes.to_vec());
+    let mut network = training_config.create_network();
+    let accuracy = trainer.train(&mut network, &normalized_train, &normalized_test);
+
+    let options = SaveOptions {
+        training_config: Some(training_config),
+        ..SaveOptions::default()
+    };
+    pickle::save_model_with_options(&network, &normalization_params, &options, out_path)?;
+    Ok(accuracy)
+}
+
+/// The French label printed for a binary prediction.
+pub fn verdict(precipitation: bool) -> &'static str {
+    if precipitation {
+        "Précipitations probables (pluie/averses)"
+    } else {
+        "Conditions dégagées (pas de précipitations)"
+    }
+}
+
+// Options that take no value, e.g. `--pre-normalized`; they are stored with the value "true".
+const BARE_FLAGS: &[&str] = &["pre-normalized"];
+
+fn parse_flags(args: &[String]) -> Result<HashMap<&str, &str>, String> {
+    let mut flags = HashMap::new();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        let name = arg
+            .strip_prefix("--")
+            .ok_or_else(|| format!("Argument inattendu : {}", arg))?;
+        if BARE_FLAGS.contains(&name) {
+            flags.insert(name, "true");
+            continue;
+        }
+        let value = args
+            .next()
+            .ok_or_else(|| format!("Valeur manquante pour --{}", name))?;
+        flags.insert(name, value.as_str());
+    }
+
+    Ok(flags)
+}
+
+fn parse_flag<T: FromStr>(flags: &HashMap<&str, &str>, name: &str) -> Result<T, String> {
+    let value = flags
+        .get(name)
+        .ok_or_else(|| format!("Option --{} requise", name))?;
+    value
+        .parse()
+        .map_err(|_| format!("Valeur invalide pour --{} : {}", name, value))
+}
+
+fn required_flag<'a>(flags: &HashMap<&str, &'a str>, name: &str) -> Result<&'a str, String> {
+    flags
+        .get(name)
+        .copied()
+        .ok_or_else(|| format!("Option --{} requise", name))
+}
+
+fn read_inputs(path: &Path) -> Result<Vec<WeatherInput>, Box<dyn Error>> {
+    let content = fs::read_to_string(path)?;
+    if path.extension().is_none_or(|extension| extension != "csv") {
+        return Ok(serde_json::from_str(&content)?);
+    }
+
+    let mut inputs = Vec::new();
+    for (line_idx, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let values: Result<Vec<f32>, _> =
+            line.split(',').map(|value| value.trim().parse()).collect();
+        match values {
+            Ok(values) if values.len() == 4 => inputs.push(WeatherInput {
+                temp: values[0],
+                pressure: values[1],
+                altitude: values[2],
+                humidity: values[3],
+            }),
+            // A non-numeric first line is the header.
+            Err(_) if line_idx == 0 => continue,
+            _ => return Err(format!("Ligne {} invalide : {}", line_idx + 1, line).into()),
+        }
+    }
+
+    Ok(inputs)
+}
+
+fn parse_flag_or<T: FromStr>(
+    flags: &HashMap<&str, &str>,
+    name: &str,
+    default: T,
+) -> Result<T, String> {
+    if flags.contains_key(name) {
+        parse_flag(flags, name)
+    } else {
+        Ok(default)
+    }
+}
+
+fn parse_hidden_sizes(hidden: &str) -> Result<Vec<usize>, String> {
+    hidden
+        .split(',')
+        .filter(|size| !size.trim().is_empty())
+        .map(|size| {
+            size.trim()
+                .parse()
+                .map_err(|_| format!("Valeur invalide pour --hidden : {}", hidden))
+        })
+        .collect()
+}
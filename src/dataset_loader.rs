@@ -1,16 +1,99 @@
-use serde::{Deserialize, Serialize};
+use flate2::read::GzDecoder;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rand_distr::{Beta, Distribution};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::error::Error;
 use std::fs::File;
-use std::io::BufReader;
+use std::io::{BufRead, BufReader, Read};
 use std::path::Path;
 
+// A missing reading is stored as NaN; in JSON it is written (and read back) as `null`.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct WeatherInput {
+    #[serde(deserialize_with = "deserialize_reading")]
     pub temp: f32,
+    #[serde(deserialize_with = "deserialize_reading")]
     pub pressure: f32,
+    #[serde(deserialize_with = "deserialize_reading")]
     pub altitude: f32,
+    #[serde(deserialize_with = "deserialize_reading")]
     pub humidity: f32,
 }
 
+impl WeatherInput {
+    pub fn to_vec(&self) -> Vec<f32> {
+        vec![self.temp, self.pressure, self.altitude, self.humidity]
+    }
+
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let checks = [
+            ("température", self.temp, -90.0, 60.0, "°C"),
+            ("pression", self.pressure, 850.0, 1100.0, "hPa"),
+            ("altitude", self.altitude, -430.0, f32::INFINITY, "m"),
+            ("humidité", self.humidity, 0.0, 100.0, "%"),
+        ];
+
+        let violations: Vec<String> = checks
+            .iter()
+            .filter_map(|&(name, value, min, max, unit)| {
+                if value.is_nan() {
+                    Some(format!("{} manquante", name))
+                } else if value < min {
+                    Some(format!(
+                        "{} {} {} inférieure à {} {}",
+                        name, value, unit, min, unit
+                    ))
+                } else if value > max {
+                    Some(format!(
+                        "{} {} {} supérieure à {} {}",
+                        name, value, unit, max, unit
+                    ))
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    pub fn has_missing(&self) -> bool {
+        self.to_vec().iter().any(|value| value.is_nan())
+    }
+
+    fn from_slice(values: &[f32]) -> Self {
+        WeatherInput {
+            temp: values[0],
+            pressure: values[1],
+            altitude: values[2],
+            humidity: values[3],
+        }
+    }
+}
+
+fn deserialize_reading<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f32, D::Error> {
+    Ok(Option::<f32>::deserialize(deserializer)?.unwrap_or(f32::NAN))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImputeStrategy {
+    Mean,
+    Median,
+    Constant(f32),
+}
+
+// Per-feature median and interquartile range, in [temp, pressure, altitude, humidity] order.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RobustParams {
+    pub medians: [f32; 4],
+    pub iqrs: [f32; 4],
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct WeatherOutput {
     pub forecast: String,
@@ -32,44 +115,398 @@ pub fn load_dataset<P: AsRef<Path>>(
     path: P,
 ) -> Result<Vec<WeatherDataPoint>, Box<dyn std::error::Error>> {
     let file = File::open(path)?;
-    let reader = BufReader::new(file);
+    load_dataset_from_reader(BufReader::new(file))
+}
+
+// Same as `load_dataset` for a gzip-compressed JSON file, e.g. `weather-train-dataset.json.gz`.
+pub fn load_dataset_gz<P: AsRef<Path>>(
+    path: P,
+) -> Result<Vec<WeatherDataPoint>, Box<dyn std::error::Error>> {
+    let file = File::open(path)?;
+    load_dataset_from_reader(BufReader::new(GzDecoder::new(file)))
+}
+
+pub fn load_dataset_from_reader<R: Read>(
+    reader: R,
+) -> Result<Vec<WeatherDataPoint>, Box<dyn std::error::Error>> {
     let data = serde_json::from_reader(reader)?;
     Ok(data)
 }
 
+// For preprocessed datasets whose `output` is already the precipitation boolean, skipping
+// the forecast keyword matching of `simplify_forecasts`.
+pub fn load_simplified_dataset<P: AsRef<Path>>(
+    path: P,
+) -> Result<Vec<SimplifiedWeatherDataPoint>, Box<dyn std::error::Error>> {
+    let file = File::open(path)?;
+    Ok(serde_json::from_reader(BufReader::new(file))?)
+}
+
+pub fn load_dataset_streaming<P: AsRef<Path>>(
+    path: P,
+) -> Result<impl Iterator<Item = Result<WeatherDataPoint, Box<dyn Error>>>, Box<dyn Error>> {
+    let file = File::open(path)?;
+    Ok(DatasetStream::new(BufReader::new(file)))
+}
+
+pub fn load_dataset_jsonl<P: AsRef<Path>>(
+    path: P,
+) -> Result<Vec<WeatherDataPoint>, Box<dyn Error>> {
+    let file = File::open(path)?;
+    let mut data = Vec::new();
+
+    for (line_idx, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let data_point = serde_json::from_str(&line)
+            .map_err(|error| format!("Ligne {} invalide : {}", line_idx + 1, error))?;
+        data.push(data_point);
+    }
+
+    Ok(data)
+}
+
+// Yields data points one by one from either a JSON array or a stream of
+// whitespace/newline separated JSON objects, without materializing the dataset.
+// A truncated array, a missing or extra comma, or data after the closing `]` is an error.
+struct DatasetStream<R: BufRead> {
+    reader: R,
+    started: bool,
+    in_array: bool,
+    read_element: bool,
+    finished: bool,
+}
+
+impl<R: BufRead> DatasetStream<R> {
+    fn new(reader: R) -> Self {
+        DatasetStream {
+            reader,
+            started: false,
+            in_array: false,
+            read_element: false,
+            finished: false,
+        }
+    }
+
+    fn peek_significant_byte(&mut self) -> std::io::Result<Option<u8>> {
+        loop {
+            let buffer = self.reader.fill_buf()?;
+            let Some(&byte) = buffer.first() else {
+                return Ok(None);
+            };
+
+            if byte.is_ascii_whitespace() {
+                self.reader.consume(1);
+            } else {
+                return Ok(Some(byte));
+            }
+        }
+    }
+
+    fn next_data_point(&mut self) -> Result<Option<WeatherDataPoint>, Box<dyn Error>> {
+        if !self.started {
+            self.started = true;
+            if self.peek_significant_byte()? == Some(b'[') {
+                self.reader.consume(1);
+                self.in_array = true;
+            }
+        }
+
+        if self.in_array {
+            match self.peek_significant_byte()? {
+                None => return Err("Tableau JSON non terminé".into()),
+                Some(b']') => return self.end_array(),
+                Some(b',') if self.read_element => {
+                    self.reader.consume(1);
+                    match self.peek_significant_byte()? {
+                        None => return Err("Tableau JSON non terminé".into()),
+                        Some(b']') => {
+                            return Err("Virgule en trop à la fin du tableau JSON".into());
+                        }
+                        Some(_) => {}
+                    }
+                }
+                Some(_) if self.read_element => {
+                    return Err("Virgule manquante entre deux éléments du tableau JSON".into());
+                }
+                Some(_) => {}
+            }
+        } else if self.peek_significant_byte()?.is_none() {
+            return Ok(None);
+        }
+
+        let mut deserializer = serde_json::Deserializer::from_reader(&mut self.reader);
+        let data_point = WeatherDataPoint::deserialize(&mut deserializer)?;
+        self.read_element = true;
+        Ok(Some(data_point))
+    }
+
+    fn end_array(&mut self) -> Result<Option<WeatherDataPoint>, Box<dyn Error>> {
+        self.reader.consume(1);
+        match self.peek_significant_byte()? {
+            None => Ok(None),
+            Some(_) => Err("Données inattendues après la fin du tableau JSON".into()),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for DatasetStream<R> {
+    type Item = Result<WeatherDataPoint, Box<dyn Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        match self.next_data_point() {
+            Ok(Some(data_point)) => Some(Ok(data_point)),
+            Ok(None) => {
+                self.finished = true;
+                None
+            }
+            Err(error) => {
+                self.finished = true;
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+// Keywords are matched on whole words, accents and case ignored, so
+// "humidité faible" does not count as precipitation. Only plurals are derived automatically,
+// the adjective forms ("pluvieux", "orageuse", ...) are listed explicitly.
+pub const FRENCH_PRECIPITATION_KEYWORDS: &[&str] = &[
+    "pluie",
+    "pluvieux",
+    "pluvieuse",
+    "averse",
+    "orage",
+    "orageux",
+    "orageuse",
+    "tonnerre",
+    "précipitation",
+    "neige",
+    "neigeux",
+    "neigeuse",
+    "rafale",
+    "humide",
+    "bruine",
+    "bruineux",
+    "bruineuse",
+    "humidité élevée",
+    "forte humidité",
+    "lourd",
+    "lourde",
+];
+
 pub fn simplify_forecasts(dataset: &[WeatherDataPoint]) -> Vec<SimplifiedWeatherDataPoint> {
-    dataset
+    simplify_forecasts_with_keywords(dataset, FRENCH_PRECIPITATION_KEYWORDS)
+}
+
+pub fn simplify_forecasts_with_keywords(
+    dataset: &[WeatherDataPoint],
+    keywords: &[&str],
+) -> Vec<SimplifiedWeatherDataPoint> {
+    // Normalized once here rather than for every forecast.
+    let keywords: Vec<Vec<String>> = keywords
         .iter()
-        .map(|data_point| {
-            let precipitation_keywords = [
-                "pluie",
-                "averse",
-                "orage",
-                "tonnerre",
-                "précipitation",
-                "neige",
-                "rafales",
-                "humide",
-                "bruine",
-                "humidité",
-                "lourd",
-            ];
-
-            let has_precipitation = precipitation_keywords
-                .iter()
-                .any(|&keyword| data_point.output.forecast.to_lowercase().contains(keyword));
+        .map(|keyword| normalized_words(keyword))
+        .collect();
 
-            SimplifiedWeatherDataPoint {
-                input: data_point.input.clone(),
-                output: has_precipitation,
-            }
+    dataset
+        .iter()
+        .map(|data_point| SimplifiedWeatherDataPoint {
+            input: data_point.input.clone(),
+            output: contains_any_keyword(&data_point.output.forecast, &keywords),
         })
         .collect()
 }
 
+// `keywords` are already split into normalized words, see `normalized_words`.
+fn contains_any_keyword(forecast: &str, keywords: &[Vec<String>]) -> bool {
+    let forecast_words = normalized_words(forecast);
+
+    keywords.iter().any(|keyword_words| {
+        !keyword_words.is_empty()
+            && forecast_words.windows(keyword_words.len()).any(|window| {
+                window
+                    .iter()
+                    .zip(keyword_words)
+                    .all(|(word, keyword_word)| word_matches(word, keyword_word))
+            })
+    })
+}
+
+// A forecast word matches a keyword word exactly or as its plural ("averses" for "averse").
+fn word_matches(word: &str, keyword_word: &str) -> bool {
+    match word.strip_prefix(keyword_word) {
+        Some(suffix) => suffix.is_empty() || suffix == "s" || suffix == "x",
+        None => false,
+    }
+}
+
+fn normalized_words(text: &str) -> Vec<String> {
+    strip_diacritics(&text.to_lowercase())
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn strip_diacritics(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => stripped.push('a'),
+            'ç' => stripped.push('c'),
+            'è' | 'é' | 'ê' | 'ë' => stripped.push('e'),
+            'ì' | 'í' | 'î' | 'ï' => stripped.push('i'),
+            'ñ' => stripped.push('n'),
+            'ò' | 'ó' | 'ô' | 'õ' | 'ö' => stripped.push('o'),
+            'ù' | 'ú' | 'û' | 'ü' => stripped.push('u'),
+            'ý' | 'ÿ' => stripped.push('y'),
+            'æ' => stripped.push_str("ae"),
+            'œ' => stripped.push_str("oe"),
+            _ => stripped.push(c),
+        }
+    }
+
+    stripped
+}
+
+// Min-max normalization params in the `[min_temp, max_temp, min_pressure, max_pressure,
+// min_altitude, max_altitude, min_humidity, max_humidity]` layout.
+pub type NormalizationParams = [f32; 8];
+
+// Normalization params that leave every feature unchanged (min 0, max 1).
+pub const IDENTITY_NORMALIZATION: NormalizationParams = [0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0];
+
+// For data that is already in [0, 1], `skip` returns it unchanged with the identity params,
+// so it is not normalized twice and inference with the returned params still works.
+pub fn normalize_inputs_or_skip(
+    dataset: &[SimplifiedWeatherDataPoint],
+    skip: bool,
+) -> (Vec<SimplifiedWeatherDataPoint>, [f32; 8]) {
+    if skip {
+        (dataset.to_vec(), IDENTITY_NORMALIZATION)
+    } else {
+        normalize_inputs(dataset)
+    }
+}
+
 pub fn normalize_inputs(
     dataset: &[SimplifiedWeatherDataPoint],
 ) -> (Vec<SimplifiedWeatherDataPoint>, [f32; 8]) {
+    let norm_params = feature_ranges(dataset);
+
+    let normalized_dataset = dataset
+        .iter()
+        .map(|data_point| SimplifiedWeatherDataPoint {
+            input: normalize_with_params(&data_point.input, &norm_params),
+            output: data_point.output,
+        })
+        .collect();
+
+    (normalized_dataset, norm_params)
+}
+
+// Like `normalize_inputs` but scales each feature to [lo, hi], e.g. [-1, 1] for tanh networks.
+// The returned params are the same min/max values; pass them with the same range to
+// `normalize_with_params_range` at inference.
+pub fn normalize_inputs_range(
+    dataset: &[SimplifiedWeatherDataPoint],
+    lo: f32,
+    hi: f32,
+) -> (Vec<SimplifiedWeatherDataPoint>, [f32; 8]) {
+    let norm_params = feature_ranges(dataset);
+
+    let normalized_dataset = dataset
+        .iter()
+        .map(|data_point| SimplifiedWeatherDataPoint {
+            input: normalize_with_params_range(&data_point.input, &norm_params, lo, hi),
+            output: data_point.output,
+        })
+        .collect();
+
+    (normalized_dataset, norm_params)
+}
+
+// Datasets from several stations share the same schema, so merging is a concatenation.
+pub fn merge_datasets(a: Vec<WeatherDataPoint>, b: Vec<WeatherDataPoint>) -> Vec<WeatherDataPoint> {
+    let mut merged = a;
+    merged.extend(b);
+    merged
+}
+
+// `merge_datasets`, plus one warning per feature whose ranges in `a` and `b` do not overlap,
+// which usually means the datasets use different units (e.g. °F and °C).
+pub fn merge_datasets_checked(
+    a: Vec<WeatherDataPoint>,
+    b: Vec<WeatherDataPoint>,
+) -> (Vec<WeatherDataPoint>, Vec<String>) {
+    let mut warnings = Vec::new();
+
+    if !a.is_empty() && !b.is_empty() {
+        let ranges_a = input_ranges(a.iter().map(|data_point| &data_point.input));
+        let ranges_b = input_ranges(b.iter().map(|data_point| &data_point.input));
+        let features = ["température", "pression", "altitude", "humidité"];
+
+        for (feature_idx, feature) in features.iter().enumerate() {
+            let (min_a, max_a) = (ranges_a[2 * feature_idx], ranges_a[2 * feature_idx + 1]);
+            let (min_b, max_b) = (ranges_b[2 * feature_idx], ranges_b[2 * feature_idx + 1]);
+
+            if max_a < min_b || max_b < min_a {
+                warnings.push(format!(
+                    "Attention : plages de {} disjointes ([{}, {}] et [{}, {}]). Les unités sont-elles les mêmes ?",
+                    feature, min_a, max_a, min_b, max_b
+                ));
+            }
+        }
+    }
+
+    (merge_datasets(a, b), warnings)
+}
+
+// Drops the features whose value never varies across `data` (missing readings ignored), as
+// they add parameters without any signal. Returns the reduced inputs together with the mask
+// of kept features, in [temp, pressure, altitude, humidity] order; apply the same mask at
+// inference with `apply_feature_mask`.
+pub fn drop_constant_features(data: &[SimplifiedWeatherDataPoint]) -> (Vec<Vec<f32>>, Vec<bool>) {
+    let ranges = feature_ranges(data);
+    // A feature that is missing everywhere has min > max and is dropped too.
+    let mask: Vec<bool> = ranges
+        .chunks_exact(2)
+        .map(|range| range[1] - range[0] > f32::EPSILON)
+        .collect();
+
+    let reduced = data
+        .iter()
+        .map(|data_point| apply_feature_mask(&data_point.input, &mask))
+        .collect();
+
+    (reduced, mask)
+}
+
+pub fn apply_feature_mask(input: &WeatherInput, mask: &[bool]) -> Vec<f32> {
+    input
+        .to_vec()
+        .into_iter()
+        .zip(mask)
+        .filter(|&(_, &kept)| kept)
+        .map(|(value, _)| value)
+        .collect()
+}
+
+// Min and max values of each feature, in the `[min_temp, max_temp, min_pressure, ...]` layout.
+fn feature_ranges(dataset: &[SimplifiedWeatherDataPoint]) -> [f32; 8] {
+    input_ranges(dataset.iter().map(|data_point| &data_point.input))
+}
+
+fn input_ranges<'a>(inputs: impl Iterator<Item = &'a WeatherInput>) -> [f32; 8] {
     // Find min and max values for each feature
     let mut min_temp = f32::MAX;
     let mut max_temp = f32::MIN;
@@ -80,18 +517,18 @@ pub fn normalize_inputs(
     let mut min_humidity = f32::MAX;
     let mut max_humidity = f32::MIN;
 
-    for data_point in dataset {
-        min_temp = min_temp.min(data_point.input.temp);
-        max_temp = max_temp.max(data_point.input.temp);
-        min_pressure = min_pressure.min(data_point.input.pressure);
-        max_pressure = max_pressure.max(data_point.input.pressure);
-        min_altitude = min_altitude.min(data_point.input.altitude);
-        max_altitude = max_altitude.max(data_point.input.altitude);
-        min_humidity = min_humidity.min(data_point.input.humidity);
-        max_humidity = max_humidity.max(data_point.input.humidity);
+    for input in inputs {
+        min_temp = min_temp.min(input.temp);
+        max_temp = max_temp.max(input.temp);
+        min_pressure = min_pressure.min(input.pressure);
+        max_pressure = max_pressure.max(input.pressure);
+        min_altitude = min_altitude.min(input.altitude);
+        max_altitude = max_altitude.max(input.altitude);
+        min_humidity = min_humidity.min(input.humidity);
+        max_humidity = max_humidity.max(input.humidity);
     }
 
-    let norm_params = [
+    [
         min_temp,
         max_temp,
         min_pressure,
@@ -100,32 +537,7 @@ pub fn normalize_inputs(
         max_altitude,
         min_humidity,
         max_humidity,
-    ];
-
-    let normalized_dataset = dataset
-        .iter()
-        .map(|data_point| {
-            let normalized_temp = (data_point.input.temp - min_temp) / (max_temp - min_temp);
-            let normalized_pressure =
-                (data_point.input.pressure - min_pressure) / (max_pressure - min_pressure);
-            let normalized_altitude =
-                (data_point.input.altitude - min_altitude) / (max_altitude - min_altitude);
-            let normalized_humidity =
-                (data_point.input.humidity - min_humidity) / (max_humidity - min_humidity);
-
-            SimplifiedWeatherDataPoint {
-                input: WeatherInput {
-                    temp: normalized_temp,
-                    pressure: normalized_pressure,
-                    altitude: normalized_altitude,
-                    humidity: normalized_humidity,
-                },
-                output: data_point.output,
-            }
-        })
-        .collect();
-
-    (normalized_dataset, norm_params)
+    ]
 }
 
 pub fn normalize_with_params(input: &WeatherInput, params: &[f32; 8]) -> WeatherInput {
@@ -139,27 +551,284 @@ pub fn normalize_with_params(input: &WeatherInput, params: &[f32; 8]) -> Weather
     let max_humidity = params[7];
 
     WeatherInput {
-        temp: (input.temp - min_temp) / (max_temp - min_temp),
-        pressure: (input.pressure - min_pressure) / (max_pressure - min_pressure),
-        altitude: (input.altitude - min_altitude) / (max_altitude - min_altitude),
-        humidity: (input.humidity - min_humidity) / (max_humidity - min_humidity),
+        temp: min_max_scale(input.temp, min_temp, max_temp),
+        pressure: min_max_scale(input.pressure, min_pressure, max_pressure),
+        altitude: min_max_scale(input.altitude, min_altitude, max_altitude),
+        humidity: min_max_scale(input.humidity, min_humidity, max_humidity),
     }
 }
 
-pub fn prepare_inputs(dataset: &[SimplifiedWeatherDataPoint]) -> Vec<Vec<f32>> {
+pub fn normalize_with_params_range(
+    input: &WeatherInput,
+    params: &[f32; 8],
+    lo: f32,
+    hi: f32,
+) -> WeatherInput {
+    let unit = normalize_with_params(input, params);
+    let rescale = |value: f32| lo + value * (hi - lo);
+
+    WeatherInput {
+        temp: rescale(unit.temp),
+        pressure: rescale(unit.pressure),
+        altitude: rescale(unit.altitude),
+        humidity: rescale(unit.humidity),
+    }
+}
+
+// Robust scaling: (value - median) / IQR per feature, so a few outliers barely move the
+// scale of the bulk of the data, unlike min-max scaling.
+pub fn robust_scale_inputs(
+    dataset: &[SimplifiedWeatherDataPoint],
+) -> (Vec<SimplifiedWeatherDataPoint>, RobustParams) {
+    let mut params = RobustParams {
+        medians: [0.0; 4],
+        iqrs: [0.0; 4],
+    };
+
+    for feature_idx in 0..4 {
+        let mut values: Vec<f32> = dataset
+            .iter()
+            .map(|data_point| data_point.input.to_vec()[feature_idx])
+            .collect();
+        if values.is_empty() {
+            continue;
+        }
+        values.sort_by(f32::total_cmp);
+
+        params.medians[feature_idx] = quantile(&values, 0.5);
+        params.iqrs[feature_idx] = quantile(&values, 0.75) - quantile(&values, 0.25);
+    }
+
+    let scaled_dataset = dataset
+        .iter()
+        .map(|data_point| SimplifiedWeatherDataPoint {
+            input: robust_scale_with_params(&data_point.input, &params),
+            output: data_point.output,
+        })
+        .collect();
+
+    (scaled_dataset, params)
+}
+
+// A feature whose middle half never varies (IQR == 0) is only centered, not divided.
+pub fn robust_scale_with_params(input: &WeatherInput, params: &RobustParams) -> WeatherInput {
+    let values: Vec<f32> = input
+        .to_vec()
+        .iter()
+        .zip(params.medians.iter().zip(&params.iqrs))
+        .map(|(&value, (&median, &iqr))| {
+            if iqr.abs() <= f32::EPSILON {
+                value - median
+            } else {
+                (value - median) / iqr
+            }
+        })
+        .collect();
+
+    WeatherInput::from_slice(&values)
+}
+
+pub fn normalize_batch(inputs: &[WeatherInput], params: &[f32; 8]) -> Vec<Vec<f32>> {
+    inputs
+        .iter()
+        .map(|input| normalize_with_params(input, params).to_vec())
+        .collect()
+}
+
+// `normalize_batch` for params computed elsewhere (e.g. by a separate preprocessing service),
+// rejecting them unless max > min for every feature instead of silently mapping the feature to 0.
+pub fn apply_scaler(
+    inputs: &[WeatherInput],
+    params: &NormalizationParams,
+) -> Result<Vec<Vec<f32>>, Box<dyn Error>> {
+    let features = ["la température", "la pression", "l'altitude", "l'humidité"];
+    for (feature, range) in features.iter().zip(params.chunks_exact(2)) {
+        let (min, max) = (range[0], range[1]);
+        if min.is_nan() || max.is_nan() || max <= min {
+            return Err(format!(
+                "Paramètres de normalisation invalides pour {} : max ({}) doit être supérieur à min ({})",
+                feature, max, min
+            )
+            .into());
+        }
+    }
+
+    Ok(normalize_batch(inputs, params))
+}
+
+// The inverse of the temperature scaling in `normalize_with_params`, for a network that
+// predicts a normalized temperature.
+pub fn denormalize_output(value: f32, params: &[f32; 8]) -> f32 {
+    let min_temp = params[0];
+    let max_temp = params[1];
+    value * (max_temp - min_temp) + min_temp
+}
+
+// A feature that never varies (max == min) would divide by zero; map it to 0 instead.
+fn min_max_scale(value: f32, min: f32, max: f32) -> f32 {
+    let range = max - min;
+    if range.abs() <= f32::EPSILON {
+        0.0
+    } else {
+        (value - min) / range
+    }
+}
+
+// Linearly interpolated quantile `q` of non-empty, ascending `sorted` values.
+fn quantile(sorted: &[f32], q: f32) -> f32 {
+    let position = q * (sorted.len() - 1) as f32;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (position - lower as f32)
+}
+
+pub fn impute_missing(
+    dataset: &[SimplifiedWeatherDataPoint],
+    strategy: ImputeStrategy,
+) -> Vec<SimplifiedWeatherDataPoint> {
+    // One replacement value per feature, computed from the readings that are present.
+    let replacements: Vec<f32> = (0..4)
+        .map(|feature_idx| {
+            let mut present: Vec<f32> = dataset
+                .iter()
+                .map(|data_point| data_point.input.to_vec()[feature_idx])
+                .filter(|value| !value.is_nan())
+                .collect();
+
+            match strategy {
+                ImputeStrategy::Constant(value) => value,
+                _ if present.is_empty() => 0.0,
+                ImputeStrategy::Mean => present.iter().sum::<f32>() / present.len() as f32,
+                ImputeStrategy::Median => {
+                    present.sort_by(f32::total_cmp);
+                    quantile(&present, 0.5)
+                }
+            }
+        })
+        .collect();
+
     dataset
         .iter()
         .map(|data_point| {
-            vec![
-                data_point.input.temp,
-                data_point.input.pressure,
-                data_point.input.altitude,
-                data_point.input.humidity,
-            ]
+            let values: Vec<f32> = data_point
+                .input
+                .to_vec()
+                .iter()
+                .zip(&replacements)
+                .map(|(&value, &replacement)| if value.is_nan() { replacement } else { value })
+                .collect();
+
+            SimplifiedWeatherDataPoint {
+                input: WeatherInput::from_slice(&values),
+                output: data_point.output,
+            }
+        })
+        .collect()
+}
+
+// (precipitation count, clear count, majority / minority ratio). The ratio is infinite when
+// one class is absent and 1.0 for an empty dataset.
+pub fn class_balance(dataset: &[SimplifiedWeatherDataPoint]) -> (usize, usize, f32) {
+    let positive_count = dataset
+        .iter()
+        .filter(|data_point| data_point.output)
+        .count();
+    let negative_count = dataset.len() - positive_count;
+
+    let majority = positive_count.max(negative_count) as f32;
+    let minority = positive_count.min(negative_count) as f32;
+    let ratio = if dataset.is_empty() {
+        1.0
+    } else {
+        majority / minority
+    };
+
+    (positive_count, negative_count, ratio)
+}
+
+// A blended feature vector and its soft label in [0, 1].
+pub type MixupSample = (Vec<f32>, f32);
+
+// Mixup augmentation: each sample i is blended with a random sample j as
+// x = λ*x_i + (1-λ)*x_j and y = λ*y_i + (1-λ)*y_j, with λ ~ Beta(alpha, alpha).
+// The soft labels suit any loss taking targets in [0, 1], e.g. `NetworkExt::backward_with_loss`.
+pub fn mixup(
+    dataset: &[SimplifiedWeatherDataPoint],
+    alpha: f32,
+    seed: u64,
+) -> Result<Vec<MixupSample>, Box<dyn Error>> {
+    let beta = Beta::new(alpha, alpha)
+        .map_err(|_| format!("Paramètre alpha invalide pour le mixup : {}", alpha))?;
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut mixed = Vec::with_capacity(dataset.len());
+    for data_point in dataset {
+        let other = &dataset[rng.random_range(0..dataset.len())];
+        let lambda = beta.sample(&mut rng);
+
+        let features = data_point
+            .input
+            .to_vec()
+            .iter()
+            .zip(other.input.to_vec())
+            .map(|(&x_i, x_j)| lambda * x_i + (1.0 - lambda) * x_j)
+            .collect();
+        let label = lambda * f32::from(u8::from(data_point.output))
+            + (1.0 - lambda) * f32::from(u8::from(other.output));
+        mixed.push((features, label));
+    }
+
+    Ok(mixed)
+}
+
+// Flattens each run of `window` consecutive observations into one input of width `4 * window`,
+// oldest first. Window i covers series[i..i + window] and is meant to predict the label of
+// step i + window, so the last observation (which has no next step) never starts a target
+// and the result has `series.len() - window` windows.
+pub fn make_windows(series: &[WeatherInput], window: usize) -> Vec<Vec<f32>> {
+    if window == 0 || series.len() <= window {
+        return Vec::new();
+    }
+
+    series
+        .windows(window)
+        .take(series.len() - window)
+        .map(|observations| observations.iter().flat_map(WeatherInput::to_vec).collect())
+        .collect()
+}
+
+// One-hot encodes each normalized feature into `bins_per_feature` equal-width buckets of
+// [0, 1], giving inputs of width `4 * bins_per_feature`. Values outside [0, 1] fall in the
+// first or last bucket; a missing (NaN) reading sets no bucket at all.
+pub fn discretize(data: &[SimplifiedWeatherDataPoint], bins_per_feature: usize) -> Vec<Vec<f32>> {
+    data.iter()
+        .map(|data_point| {
+            let mut encoded = vec![0.0; 4 * bins_per_feature];
+            if bins_per_feature == 0 {
+                return encoded;
+            }
+
+            for (feature_idx, value) in data_point.input.to_vec().into_iter().enumerate() {
+                if value.is_nan() {
+                    continue;
+                }
+                let bin =
+                    ((value * bins_per_feature as f32).max(0.0) as usize).min(bins_per_feature - 1);
+                encoded[feature_idx * bins_per_feature + bin] = 1.0;
+            }
+
+            encoded
         })
         .collect()
 }
 
+pub fn prepare_inputs(dataset: &[SimplifiedWeatherDataPoint]) -> Vec<Vec<f32>> {
+    dataset
+        .iter()
+        .map(|data_point| data_point.input.to_vec())
+        .collect()
+}
+
 pub fn prepare_outputs(dataset: &[SimplifiedWeatherDataPoint]) -> Vec<Vec<f32>> {
     dataset
         .iter()
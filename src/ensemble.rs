@@ -0,0 +1,66 @@
+use crate::dataset_loader::WeatherInput;
+use crate::metrics::InclusiveBound;
+use crate::weather_model::WeatherModel;
+
+/// A soft-voting ensemble of weather models.
+///
+/// The ensemble probability is the weighted mean of every model's output probability.
+///
+/// # Fields
+///
+/// * `models` - The models taking part in the vote.
+/// * `weights` - One finite, non-negative weight per model, not all zero; `Ensemble::new` gives every
+///   model the same weight.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ensemble {
+    pub models: Vec<WeatherModel>,
+    pub weights: Vec<f32>,
+}
+
+impl Ensemble {
+    pub fn new(models: Vec<WeatherModel>) -> Result<Self, String> {
+        let weights = vec![1.0; models.len()];
+        Ensemble::with_weights(models, weights)
+    }
+
+    pub fn with_weights(models: Vec<WeatherModel>, weights: Vec<f32>) -> Result<Self, String> {
+        if models.is_empty() {
+            return Err("L'ensemble doit contenir au moins un modèle".to_string());
+        }
+        if models.len() != weights.len() {
+            return Err(format!(
+                "Attendu {} poids pour {} modèles, reçu {}",
+                models.len(),
+                models.len(),
+                weights.len()
+            ));
+        }
+        if weights
+            .iter()
+            .any(|&weight| !weight.is_finite() || weight < 0.0)
+            || weights.iter().sum::<f32>() <= 0.0
+        {
+            return Err(
+                "Les poids de l'ensemble doivent être finis, positifs et non tous nuls".to_string(),
+            );
+        }
+
+        Ok(Ensemble { models, weights })
+    }
+
+    pub fn predict_proba(&self, input: &WeatherInput) -> f32 {
+        let total_weight: f32 = self.weights.iter().sum();
+        let weighted_sum: f32 = self
+            .models
+            .iter()
+            .zip(&self.weights)
+            .map(|(model, &weight)| weight * model.predict_proba(input))
+            .sum();
+
+        weighted_sum / total_weight
+    }
+
+    pub fn predict(&self, input: &WeatherInput, threshold: f32) -> bool {
+        InclusiveBound::AtLeast.is_positive(self.predict_proba(input), threshold)
+    }
+}
@@ -0,0 +1,196 @@
+use crate::activation::ActivationFunction;
+use crate::dataset_loader::NormalizationParams;
+use crate::neural_network::NeuralNetwork;
+use std::fmt::Write;
+
+const FEATURES: [&str; 4] = ["temp", "pressure", "altitude", "humidity"];
+
+const DOT_FUNCTION: &str = "
+static float dot(const float *weights, const float *inputs, int count) {
+    float sum = 0.0f;
+    for (int i = 0; i < count; i++) {
+        sum += weights[i] * inputs[i];
+    }
+    return sum;
+}
+
+";
+
+/// Generates self-contained C source defining
+/// `float predict(float temp, float pressure, float altitude, float humidity)`, which
+/// normalizes the raw readings with `params` and returns the network's first output.
+///
+/// Every weight and bias is baked in as a `static const float` array, one pair per layer,
+/// so the file compiles on a microcontroller with only `<math.h>`. Only the sigmoid, relu,
+/// tanh and linear activations have a C counterpart; any other one is an error, as are
+/// non-finite parameters and a network that does not take the four weather features.
+pub fn export_c(network: &NeuralNetwork, params: &NormalizationParams) -> Result<String, String> {
+    let mut activations = Vec::new();
+    let mut input_width = FEATURES.len();
+
+    for layer in &network.layers {
+        for neuron in &layer.neurons {
+            c_activation_name(neuron.activation_function)?;
+            if !activations.contains(&neuron.activation_function) {
+                activations.push(neuron.activation_function);
+            }
+            if neuron.weights.len() != input_width {
+                return Err(format!(
+                    "Le neurone {} de la couche {} a {} poids pour {} entrées",
+                    neuron.id,
+                    layer.id,
+                    neuron.weights.len(),
+                    input_width
+                ));
+            }
+            if neuron
+                .weights
+                .iter()
+                .chain([&neuron.bias])
+                .any(|value| !value.is_finite())
+            {
+                return Err(format!(
+                    "Le neurone {} de la couche {} a un paramètre non fini",
+                    neuron.id, layer.id
+                ));
+            }
+        }
+        input_width = layer.neurons.len();
+    }
+    if network.layers.is_empty() || network.layers.iter().any(|l| l.neurons.is_empty()) {
+        return Err("Le réseau n'a aucune couche ou une couche vide".to_string());
+    }
+
+    // Writing to a String cannot fail, hence the `unwrap`s below.
+    let mut source = String::new();
+    writeln!(source, "/* Generated by forecast_nn. */").unwrap();
+    writeln!(source, "#include <math.h>\n").unwrap();
+
+    for activation in &activations {
+        let name = c_activation_name(*activation)?;
+        let body = match activation {
+            ActivationFunction::Sigmoid => "1.0f / (1.0f + expf(-x))",
+            ActivationFunction::Relu => "x > 0.0f ? x : 0.0f",
+            ActivationFunction::Tanh => "tanhf(x)",
+            _ => "x",
+        };
+        writeln!(
+            source,
+            "static inline float {}(float x) {{ return {}; }}",
+            name, body
+        )
+        .unwrap();
+    }
+    source.push_str(DOT_FUNCTION);
+
+    input_width = FEATURES.len();
+    for (layer_idx, layer) in network.layers.iter().enumerate() {
+        let rows: Vec<String> = layer
+            .neurons
+            .iter()
+            .map(|neuron| format!("    {{{}}}", c_floats(&neuron.weights)))
+            .collect();
+        writeln!(
+            source,
+            "static const float LAYER_{}_WEIGHTS[{}][{}] = {{\n{}\n}};",
+            layer_idx,
+            layer.neurons.len(),
+            input_width,
+            rows.join(",\n")
+        )
+        .unwrap();
+
+        let biases: Vec<f32> = layer
+            .neurons
+            .iter()
+            .map(|neuron| neuron.effective_bias())
+            .collect();
+        writeln!(
+            source,
+            "static const float LAYER_{}_BIASES[{}] = {{{}}};\n",
+            layer_idx,
+            layer.neurons.len(),
+            c_floats(&biases)
+        )
+        .unwrap();
+
+        input_width = layer.neurons.len();
+    }
+
+    writeln!(
+        source,
+        "float predict(float temp, float pressure, float altitude, float humidity) {{"
+    )
+    .unwrap();
+    // Same min-max scaling as `normalize_with_params`, a constant feature mapping to 0.
+    let scaled: Vec<String> = FEATURES
+        .iter()
+        .zip(params.chunks_exact(2))
+        .map(|(feature, range)| {
+            let (min, max) = (range[0], range[1]);
+            if (max - min).abs() <= f32::EPSILON {
+                "0.0f".to_string()
+            } else {
+                format!("({} - {}) / {}", feature, c_float(min), c_float(max - min))
+            }
+        })
+        .collect();
+    writeln!(
+        source,
+        "    float layer_input[4] = {{{}}};",
+        scaled.join(", ")
+    )
+    .unwrap();
+
+    let mut previous = "layer_input".to_string();
+    for (layer_idx, layer) in network.layers.iter().enumerate() {
+        let current = format!("layer_{}", layer_idx);
+        writeln!(source, "    float {}[{}];", current, layer.neurons.len()).unwrap();
+        for (neuron_idx, neuron) in layer.neurons.iter().enumerate() {
+            writeln!(
+                source,
+                "    {}[{}] = {}(dot(LAYER_{}_WEIGHTS[{}], {}, {}) + LAYER_{}_BIASES[{}]);",
+                current,
+                neuron_idx,
+                c_activation_name(neuron.activation_function)?,
+                layer_idx,
+                neuron_idx,
+                previous,
+                neuron.weights.len(),
+                layer_idx,
+                neuron_idx
+            )
+            .unwrap();
+        }
+        previous = current;
+    }
+    writeln!(source, "    return {}[0];\n}}", previous).unwrap();
+
+    Ok(source)
+}
+
+fn c_activation_name(activation: ActivationFunction) -> Result<&'static str, String> {
+    match activation {
+        ActivationFunction::Sigmoid => Ok("activation_sigmoid"),
+        ActivationFunction::Relu => Ok("activation_relu"),
+        ActivationFunction::Tanh => Ok("activation_tanh"),
+        ActivationFunction::Linear => Ok("activation_linear"),
+        other => Err(format!(
+            "La fonction d'activation {} n'est pas prise en charge par l'export C",
+            other
+        )),
+    }
+}
+
+// `{:?}` always keeps a decimal point or an exponent, so the `f` suffix yields a valid C float.
+fn c_float(value: f32) -> String {
+    format!("{:?}f", value)
+}
+
+fn c_floats(values: &[f32]) -> String {
+    values
+        .iter()
+        .map(|&value| c_float(value))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
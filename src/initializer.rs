@@ -0,0 +1,155 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rand_distr::{Distribution, Normal};
+use serde::{Deserialize, Serialize};
+
+/// Weight initialization schemes used to (re)draw neuron weights.
+///
+/// # Variants
+///
+/// * `XavierUniform` - Glorot/Xavier uniform, `U(-sqrt(6 / (fan_in + fan_out)), sqrt(6 / (fan_in + fan_out)))`.
+///   This is the scheme used by `BinaryTrainer::create_weather_network`.
+/// * `HeNormal` - He/Kaiming normal, `N(0, 2 / fan_in)`, suited to ReLU layers.
+/// * `HeUniform` - He/Kaiming uniform, `U(-sqrt(6 / fan_in), sqrt(6 / fan_in))`, with the same
+///   `2 / fan_in` variance as `HeNormal`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum InitScheme {
+    XavierUniform,
+    HeNormal,
+    HeUniform,
+}
+
+impl InitScheme {
+    pub fn sample_weight<R: Rng>(&self, fan_in: usize, fan_out: usize, rng: &mut R) -> f32 {
+        match self {
+            InitScheme::XavierUniform => {
+                let weight_scale = (6.0 / (fan_in + fan_out) as f32).sqrt();
+                rng.random_range(-weight_scale..weight_scale)
+            }
+            InitScheme::HeNormal => {
+                let std_dev = (2.0 / fan_in.max(1) as f32).sqrt();
+                Normal::new(0.0, std_dev).unwrap().sample(rng)
+            }
+            InitScheme::HeUniform => {
+                let bound = (6.0 / fan_in.max(1) as f32).sqrt();
+                rng.random_range(-bound..bound)
+            }
+        }
+    }
+
+    pub fn sample_bias<R: Rng>(&self, rng: &mut R) -> f32 {
+        rng.random_range(-0.1..0.1)
+    }
+
+    /// The variance of the weights drawn by the scheme: `2 / (fan_in + fan_out)` for
+    /// Xavier, `2 / fan_in` for the He schemes.
+    pub fn variance(&self, fan_in: usize, fan_out: usize) -> f32 {
+        match self {
+            InitScheme::XavierUniform => 2.0 / (fan_in + fan_out).max(1) as f32,
+            InitScheme::HeNormal | InitScheme::HeUniform => 2.0 / fan_in.max(1) as f32,
+        }
+    }
+}
+
+/// The shape of the distribution weights are drawn from, independently of their variance.
+///
+/// # Variants
+///
+/// * `Uniform` - `U(-sqrt(3 * variance), sqrt(3 * variance))`.
+/// * `Normal` - `N(0, variance)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WeightDistribution {
+    Uniform,
+    Normal,
+}
+
+impl WeightDistribution {
+    pub fn sample<R: Rng>(&self, variance: f32, rng: &mut R) -> f32 {
+        match self {
+            WeightDistribution::Uniform => {
+                let bound = (3.0 * variance).sqrt();
+                rng.random_range(-bound..bound)
+            }
+            WeightDistribution::Normal => Normal::new(0.0, variance.sqrt()).unwrap().sample(rng),
+        }
+    }
+}
+
+/// Configuration of the random initialization of a new network.
+///
+/// # Fields
+///
+/// * `scheme` - The scheme used to draw the weights.
+/// * `distribution` - When set, draws the weights from this distribution with the variance of `scheme`
+///   instead of the scheme's own distribution, e.g. an `XavierUniform` variance with normal weights.
+/// * `weight_scale` - When set, overrides the scheme and draws every weight from `U(-weight_scale, weight_scale)`.
+/// * `bias_range` - The `(low, high)` range biases are uniformly drawn from.
+/// * `seed` - When set, makes the initialization reproducible.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InitConfig {
+    pub scheme: InitScheme,
+    #[serde(default)]
+    pub distribution: Option<WeightDistribution>,
+    pub weight_scale: Option<f32>,
+    pub bias_range: (f32, f32),
+    pub seed: Option<u64>,
+}
+
+impl Default for InitConfig {
+    fn default() -> Self {
+        InitConfig {
+            scheme: InitScheme::XavierUniform,
+            distribution: None,
+            weight_scale: None,
+            bias_range: (-0.1, 0.1),
+            seed: None,
+        }
+    }
+}
+
+impl InitConfig {
+    pub fn rng(&self) -> StdRng {
+        match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_os_rng(),
+        }
+    }
+
+    /// Checks the ranges the weights and biases are drawn from: `weight_scale` must be finite
+    /// and strictly positive, and `bias_range` a finite `(low, high)` with `low < high`.
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(weight_scale) = self.weight_scale
+            && !(weight_scale.is_finite() && weight_scale > 0.0)
+        {
+            return Err(format!(
+                "L'échelle des poids doit être finie et strictement positive, reçu {}",
+                weight_scale
+            ));
+        }
+
+        let (low, high) = self.bias_range;
+        if !(low.is_finite() && high.is_finite() && low < high) {
+            return Err(format!(
+                "L'intervalle des biais ({}, {}) doit être fini et croissant",
+                low, high
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub fn sample_weight<R: Rng>(&self, fan_in: usize, fan_out: usize, rng: &mut R) -> f32 {
+        match (self.weight_scale, self.distribution) {
+            (Some(weight_scale), _) => rng.random_range(-weight_scale..weight_scale),
+            (None, Some(distribution)) => {
+                distribution.sample(self.scheme.variance(fan_in, fan_out), rng)
+            }
+            (None, None) => self.scheme.sample_weight(fan_in, fan_out, rng),
+        }
+    }
+
+    pub fn sample_bias<R: Rng>(&self, rng: &mut R) -> f32 {
+        let (low, high) = self.bias_range;
+        rng.random_range(low..high)
+    }
+}
@@ -1,4 +1,6 @@
+use crate::activation::ActivationFunction;
 use crate::neuron::Neuron;
+use num_traits::Float;
 use serde::{Deserialize, Serialize};
 
 /// Represents a layer in a neural network.
@@ -8,6 +10,8 @@ use serde::{Deserialize, Serialize};
 /// - `name`: The name of the layer.
 /// - `neurons`: A vector of neurons that belong to this layer.
 ///
+/// Like `Neuron`, the layer is generic over its float type `T` and defaults to `f32`.
+///
 /// ## Methods
 ///
 /// ### `new`
@@ -24,6 +28,24 @@ use serde::{Deserialize, Serialize};
 ///
 /// -------------------------------------
 ///
+/// ### `new_mixed`
+/// Creates a layer whose neurons each have their own activation function.
+///
+/// #### Parameters:
+/// - `id`: A unique identifier for the layer.
+/// - `name`: The name of the layer; neuron `i` is named `"{name}_{i}"` and gets ID `i`.
+/// - `specs`: The activation function and weights of each neuron. Biases start at zero.
+///
+/// ```rust
+/// let layer = Layer::new_mixed(
+///     1,
+///     "Hidden Layer".to_string(),
+///     &[(ActivationFunction::Relu, vec![0.5, -0.2]), (ActivationFunction::Tanh, vec![0.1, 0.3])],
+/// );
+/// ```
+///
+/// -------------------------------------
+///
 /// ### `add_neuron`
 /// Adds a neuron to the layer.
 ///
@@ -139,7 +161,7 @@ use serde::{Deserialize, Serialize};
 /// println!("Activation functions: {:?}", functions);
 /// ```
 /// #### Returns:
-/// A vector of activation function names.
+/// A vector of the neurons' activation functions.
 ///
 /// -------------------------------------
 ///
@@ -201,7 +223,7 @@ use serde::{Deserialize, Serialize};
 /// - `activation_function`: The new activation function to set.
 ///
 /// ```rust
-/// layer.set_neuron_activation_function(1, "relu".to_string());
+/// layer.set_neuron_activation_function(1, ActivationFunction::Relu);
 /// ```
 ///
 /// -------------------------------------
@@ -230,18 +252,35 @@ use serde::{Deserialize, Serialize};
 /// layer.set_neuron_id(1, 2);
 /// ```
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Layer {
+pub struct Layer<T = f32> {
     pub id: u32,
     pub name: String,
-    pub neurons: Vec<Neuron>,
+    pub neurons: Vec<Neuron<T>>,
 }
 
-impl Layer {
-    pub fn new(id: u32, name: String, neurons: Vec<Neuron>) -> Self {
+impl<T: Float> Layer<T> {
+    pub fn new(id: u32, name: String, neurons: Vec<Neuron<T>>) -> Self {
+        Layer { id, name, neurons }
+    }
+
+    pub fn new_mixed(id: u32, name: String, specs: &[(ActivationFunction, Vec<T>)]) -> Self {
+        let neurons = specs
+            .iter()
+            .enumerate()
+            .map(|(i, (activation_function, weights))| {
+                Neuron::new(
+                    i as u32,
+                    format!("{}_{}", name, i),
+                    *activation_function,
+                    T::zero(),
+                    weights.clone(),
+                )
+            })
+            .collect();
         Layer { id, name, neurons }
     }
 
-    pub fn add_neuron(&mut self, neuron: Neuron) {
+    pub fn add_neuron(&mut self, neuron: Neuron<T>) {
         self.neurons.push(neuron);
     }
 
@@ -249,17 +288,17 @@ impl Layer {
         self.neurons.retain(|neuron| neuron.id != neuron_id);
     }
 
-    pub fn get_neuron(&self, neuron_id: u32) -> Option<&Neuron> {
+    pub fn get_neuron(&self, neuron_id: u32) -> Option<&Neuron<T>> {
         self.neurons.iter().find(|&neuron| neuron.id == neuron_id)
     }
 
-    pub fn get_neuron_mut(&mut self, neuron_id: u32) -> Option<&mut Neuron> {
+    pub fn get_neuron_mut(&mut self, neuron_id: u32) -> Option<&mut Neuron<T>> {
         self.neurons
             .iter_mut()
             .find(|neuron| neuron.id == neuron_id)
     }
 
-    pub fn activate(&self, inputs: &[f32]) -> Vec<f32> {
+    pub fn activate(&self, inputs: &[T]) -> Vec<T> {
         self.neurons
             .iter()
             .map(|neuron| neuron.activate(inputs))
@@ -281,37 +320,41 @@ impl Layer {
             .collect()
     }
 
-    pub fn get_neuron_activation_functions(&self) -> Vec<String> {
+    pub fn get_neuron_activation_functions(&self) -> Vec<ActivationFunction> {
         self.neurons
             .iter()
-            .map(|neuron| neuron.activation_function.clone())
+            .map(|neuron| neuron.activation_function)
             .collect()
     }
 
-    pub fn get_neuron_biases(&self) -> Vec<f32> {
+    pub fn get_neuron_biases(&self) -> Vec<T> {
         self.neurons.iter().map(|neuron| neuron.bias).collect()
     }
 
-    pub fn get_neuron_weights(&self) -> Vec<Vec<f32>> {
+    pub fn get_neuron_weights(&self) -> Vec<Vec<T>> {
         self.neurons
             .iter()
             .map(|neuron| neuron.weights.clone())
             .collect()
     }
 
-    pub fn set_neuron_weights(&mut self, neuron_id: u32, weights: Vec<f32>) {
+    pub fn set_neuron_weights(&mut self, neuron_id: u32, weights: Vec<T>) {
         if let Some(neuron) = self.neurons.iter_mut().find(|n| n.id == neuron_id) {
             neuron.weights = weights;
         }
     }
 
-    pub fn set_neuron_bias(&mut self, neuron_id: u32, bias: f32) {
+    pub fn set_neuron_bias(&mut self, neuron_id: u32, bias: T) {
         if let Some(neuron) = self.neurons.iter_mut().find(|n| n.id == neuron_id) {
             neuron.bias = bias;
         }
     }
 
-    pub fn set_neuron_activation_function(&mut self, neuron_id: u32, activation_function: String) {
+    pub fn set_neuron_activation_function(
+        &mut self,
+        neuron_id: u32,
+        activation_function: ActivationFunction,
+    ) {
         if let Some(neuron) = self.neurons.iter_mut().find(|n| n.id == neuron_id) {
             neuron.activation_function = activation_function;
         }
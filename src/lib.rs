@@ -1,7 +1,19 @@
+pub mod activation;
 pub mod back_propagation;
+pub mod baseline;
+pub mod bench;
+pub mod cli;
 pub mod dataset_loader;
+pub mod ensemble;
+pub mod export;
+pub mod initializer;
 pub mod layer;
+pub mod loss;
+pub mod metrics;
 pub mod neural_network;
 pub mod neuron;
+pub mod optimizer;
 pub mod pickle;
+pub mod quantization;
 pub mod trainer;
+pub mod weather_model;
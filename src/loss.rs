@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+
+/// Loss functions used to score network outputs against their targets.
+///
+/// `MeanSquaredError` is the half squared error `0.5 * (target - output)²`, the loss the
+/// backpropagation in `back_propagation` minimizes. `BinaryCrossEntropy` expects outputs in
+/// `(0, 1)` and clamps them away from 0 and 1 to keep the logarithms finite. `FocalLoss` is
+/// `-(1 - p_t)^gamma * ln(p_t)`, where `p_t` is the probability given to the target class; it
+/// down-weights examples that are already well classified and equals cross-entropy for `gamma = 0`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum LossFunction {
+    MeanSquaredError,
+    BinaryCrossEntropy,
+    FocalLoss { gamma: f32 },
+}
+
+const PROBABILITY_EPSILON: f32 = 1e-7;
+
+impl LossFunction {
+    /// The loss of a single `output` against its `target`.
+    pub fn loss(&self, output: f32, target: f32) -> f32 {
+        match self {
+            LossFunction::MeanSquaredError => 0.5 * (target - output).powi(2),
+            LossFunction::BinaryCrossEntropy => {
+                let output = output.clamp(PROBABILITY_EPSILON, 1.0 - PROBABILITY_EPSILON);
+                -(target * output.ln() + (1.0 - target) * (1.0 - output).ln())
+            }
+            LossFunction::FocalLoss { gamma } => {
+                let p_t = target_probability(output, target);
+                -(1.0 - p_t).powf(*gamma) * p_t.ln()
+            }
+        }
+    }
+
+    /// The derivative `dL/d(output)` of `loss` for a single output.
+    pub fn gradient(&self, output: f32, target: f32) -> f32 {
+        match self {
+            LossFunction::MeanSquaredError => output - target,
+            LossFunction::BinaryCrossEntropy => {
+                let output = output.clamp(PROBABILITY_EPSILON, 1.0 - PROBABILITY_EPSILON);
+                (output - target) / (output * (1.0 - output))
+            }
+            LossFunction::FocalLoss { gamma } => {
+                let p_t = target_probability(output, target);
+                let d_loss_d_p_t = gamma * (1.0 - p_t).powf(gamma - 1.0) * p_t.ln()
+                    - (1.0 - p_t).powf(*gamma) / p_t;
+                // d(p_t)/d(output) = 2t - 1
+                d_loss_d_p_t * (2.0 * target - 1.0)
+            }
+        }
+    }
+}
+
+// Probability given to the target class, clamped so the logarithm and powers stay finite.
+fn target_probability(output: f32, target: f32) -> f32 {
+    let output = output.clamp(PROBABILITY_EPSILON, 1.0 - PROBABILITY_EPSILON);
+    target * output + (1.0 - target) * (1.0 - output)
+}
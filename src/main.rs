@@ -1,8 +1,40 @@
 use std::path::Path;
 
-use forecast_nn::{dataset_loader, pickle, trainer};
+use forecast_nn::metrics::InclusiveBound;
+use forecast_nn::{cli, dataset_loader, pickle, trainer};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+// Prints the library's info-level (and more severe) log records, e.g. the training progress.
+struct ConsoleLogger;
+
+impl Log for ConsoleLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Info
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            println!("{}", record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: ConsoleLogger = ConsoleLogger;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    log::set_logger(&LOGGER).map_err(|error| error.to_string())?;
+    log::set_max_level(LevelFilter::Info);
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    // `--pre-normalized` alone keeps the default run, for datasets already in [0, 1].
+    let pre_normalized = args == ["--pre-normalized"];
+    if !args.is_empty() && !pre_normalized {
+        println!("{}", cli::run(&args)?);
+        return Ok(());
+    }
+
     println!("Réseau de neurones pour la prévision météorologique (Classification binaire)");
 
     let train_data_path = Path::new("weather-train-dataset.json");
@@ -25,9 +57,16 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     println!("Normalisation des données");
     let (normalized_train, normalization_params) =
-        dataset_loader::normalize_inputs(&binary_train_data);
+        dataset_loader::normalize_inputs_or_skip(&binary_train_data, pre_normalized);
 
-    let (normalized_test, _) = dataset_loader::normalize_inputs(&binary_test_data);
+    // The test set is scaled with the training ranges, as it will be at inference.
+    let normalized_test: Vec<dataset_loader::SimplifiedWeatherDataPoint> = binary_test_data
+        .iter()
+        .map(|data_point| dataset_loader::SimplifiedWeatherDataPoint {
+            input: dataset_loader::normalize_with_params(&data_point.input, &normalization_params),
+            output: data_point.output,
+        })
+        .collect();
 
     let train_precipitation = normalized_train.iter().filter(|d| d.output).count();
     let train_clear = normalized_train.len() - train_precipitation;
@@ -91,11 +130,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("Valeur brute de la prédiction : {:.4}", prediction);
     println!(
         "Prédiction binaire : {}",
-        if prediction >= 0.5 {
-            "Précipitations probables (pluie/averses)"
-        } else {
-            "Conditions dégagées (pas de précipitations)"
-        }
+        cli::verdict(InclusiveBound::AtLeast.is_positive(prediction, 0.5))
     );
 
     Ok(())
@@ -0,0 +1,426 @@
+use crate::back_propagation::NetworkExt;
+use crate::dataset_loader::SimplifiedWeatherDataPoint;
+use crate::loss::LossFunction;
+use crate::neural_network::NeuralNetwork;
+use crate::trainer::VecExt;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+const PERMUTATION_SEED: u64 = 42;
+
+/// Which side of the threshold an output exactly equal to it falls on.
+///
+/// # Variants
+///
+/// * `AtLeast` - `output >= threshold` is positive, so an output of exactly 0.5 predicts
+///   precipitation at the default threshold. This is the behavior of every API taking a bare threshold.
+/// * `GreaterThan` - Only `output > threshold` is positive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum InclusiveBound {
+    #[default]
+    AtLeast,
+    GreaterThan,
+}
+
+impl InclusiveBound {
+    /// Whether `output` is classified as positive at `threshold`.
+    pub fn is_positive(&self, output: f32, threshold: f32) -> bool {
+        match self {
+            InclusiveBound::AtLeast => output >= threshold,
+            InclusiveBound::GreaterThan => output > threshold,
+        }
+    }
+}
+
+/// Fraction of samples whose thresholded network output matches the binary target.
+///
+/// An output exactly at `threshold` counts as positive; see `binary_accuracy_with_bound`.
+pub fn binary_accuracy(
+    network: &NeuralNetwork,
+    inputs: &[Vec<f32>],
+    targets: &[Vec<f32>],
+    threshold: f32,
+) -> f32 {
+    binary_accuracy_with_bound(network, inputs, targets, threshold, InclusiveBound::AtLeast)
+}
+
+/// `binary_accuracy` with an explicit `bound` for outputs exactly at `threshold`.
+pub fn binary_accuracy_with_bound(
+    network: &NeuralNetwork,
+    inputs: &[Vec<f32>],
+    targets: &[Vec<f32>],
+    threshold: f32,
+    bound: InclusiveBound,
+) -> f32 {
+    let mut correct = 0;
+
+    for (i, input) in inputs.iter().enumerate() {
+        let prediction = network.predict_proba(input);
+        let target = targets[i][0];
+
+        let predicted_class = if bound.is_positive(prediction, threshold) {
+            1.0
+        } else {
+            0.0
+        };
+
+        if predicted_class == target {
+            correct += 1;
+        }
+    }
+
+    correct as f32 / inputs.len() as f32
+}
+
+/// Counts of thresholded predictions against binary targets, precipitation being the
+/// positive class.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfusionMatrix {
+    pub true_positives: usize,
+    pub false_positives: usize,
+    pub true_negatives: usize,
+    pub false_negatives: usize,
+}
+
+impl ConfusionMatrix {
+    pub fn total(&self) -> usize {
+        self.true_positives + self.false_positives + self.true_negatives + self.false_negatives
+    }
+
+    /// Compact JSON rendering, e.g. to append to a training log.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("a confusion matrix always serializes")
+    }
+}
+
+/// Renders the matrix as a grid with predictions as rows and actual classes as columns.
+impl fmt::Display for ConfusionMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<15} | {:>13} | {:>13}",
+            "Prédit \\ Réel", "Clair", "Précipitation"
+        )?;
+        writeln!(
+            f,
+            "{:<15} | {:>13} | {:>13}",
+            "Clair", self.true_negatives, self.false_negatives
+        )?;
+        write!(
+            f,
+            "{:<15} | {:>13} | {:>13}",
+            "Précipitation", self.false_positives, self.true_positives
+        )
+    }
+}
+
+/// Confusion matrix of the network's predictions at `threshold`, an output exactly at
+/// `threshold` counting as positive.
+pub fn confusion_matrix(
+    network: &NeuralNetwork,
+    inputs: &[Vec<f32>],
+    targets: &[Vec<f32>],
+    threshold: f32,
+) -> ConfusionMatrix {
+    confusion_matrix_with_bound(network, inputs, targets, threshold, InclusiveBound::AtLeast)
+}
+
+/// `confusion_matrix` with an explicit `bound` for outputs exactly at `threshold`.
+pub fn confusion_matrix_with_bound(
+    network: &NeuralNetwork,
+    inputs: &[Vec<f32>],
+    targets: &[Vec<f32>],
+    threshold: f32,
+    bound: InclusiveBound,
+) -> ConfusionMatrix {
+    confusion_from_scores(&scores(network, inputs), targets, threshold, bound)
+}
+
+/// (clear accuracy, precipitation accuracy) at `threshold`, i.e. the recall of each class.
+///
+/// A class absent from `targets` gets an accuracy of 0. An output exactly at `threshold`
+/// counts as positive.
+pub fn per_class_accuracy(
+    network: &NeuralNetwork,
+    inputs: &[Vec<f32>],
+    targets: &[Vec<f32>],
+    threshold: f32,
+) -> (f32, f32) {
+    per_class_accuracy_with_bound(network, inputs, targets, threshold, InclusiveBound::AtLeast)
+}
+
+/// `per_class_accuracy` with an explicit `bound` for outputs exactly at `threshold`.
+pub fn per_class_accuracy_with_bound(
+    network: &NeuralNetwork,
+    inputs: &[Vec<f32>],
+    targets: &[Vec<f32>],
+    threshold: f32,
+    bound: InclusiveBound,
+) -> (f32, f32) {
+    let confusion = confusion_matrix_with_bound(network, inputs, targets, threshold, bound);
+    let ratio = |correct: usize, wrong: usize| {
+        if correct + wrong == 0 {
+            0.0
+        } else {
+            correct as f32 / (correct + wrong) as f32
+        }
+    };
+
+    (
+        ratio(confusion.true_negatives, confusion.false_positives),
+        ratio(confusion.true_positives, confusion.false_negatives),
+    )
+}
+
+/// The `n` samples whose predicted probability is closest to 0.5, most uncertain first,
+/// e.g. to choose which samples to label next in active learning.
+pub fn most_uncertain<'a>(
+    network: &NeuralNetwork,
+    data: &'a [SimplifiedWeatherDataPoint],
+    n: usize,
+) -> Vec<&'a SimplifiedWeatherDataPoint> {
+    let mut ranked: Vec<(f32, &SimplifiedWeatherDataPoint)> = data
+        .iter()
+        .map(|data_point| {
+            let probability = network.predict_proba(&data_point.input.to_vec());
+            ((probability - 0.5).abs(), data_point)
+        })
+        .collect();
+    ranked.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    ranked
+        .into_iter()
+        .take(n)
+        .map(|(_, data_point)| data_point)
+        .collect()
+}
+
+/// One classification report per threshold, activating the network once per input.
+///
+/// An output exactly at a threshold counts as positive.
+pub fn threshold_sweep(
+    network: &NeuralNetwork,
+    inputs: &[Vec<f32>],
+    targets: &[Vec<f32>],
+    thresholds: &[f32],
+) -> Vec<ClassificationReport> {
+    threshold_sweep_with_bound(
+        network,
+        inputs,
+        targets,
+        thresholds,
+        InclusiveBound::AtLeast,
+    )
+}
+
+/// `threshold_sweep` with an explicit `bound` for outputs exactly at a threshold.
+pub fn threshold_sweep_with_bound(
+    network: &NeuralNetwork,
+    inputs: &[Vec<f32>],
+    targets: &[Vec<f32>],
+    thresholds: &[f32],
+    bound: InclusiveBound,
+) -> Vec<ClassificationReport> {
+    let scores = scores(network, inputs);
+
+    thresholds
+        .iter()
+        .map(|&threshold| {
+            ClassificationReport::from_confusion_matrix(confusion_from_scores(
+                &scores, targets, threshold, bound,
+            ))
+        })
+        .collect()
+}
+
+fn scores(network: &NeuralNetwork, inputs: &[Vec<f32>]) -> Vec<f32> {
+    inputs
+        .iter()
+        .map(|input| network.predict_proba(input))
+        .collect()
+}
+
+fn confusion_from_scores(
+    scores: &[f32],
+    targets: &[Vec<f32>],
+    threshold: f32,
+    bound: InclusiveBound,
+) -> ConfusionMatrix {
+    let mut matrix = ConfusionMatrix::default();
+
+    for (&score, target) in scores.iter().zip(targets) {
+        let predicted = bound.is_positive(score, threshold);
+        let actual = target[0] >= 0.5;
+
+        match (predicted, actual) {
+            (true, true) => matrix.true_positives += 1,
+            (true, false) => matrix.false_positives += 1,
+            (false, false) => matrix.true_negatives += 1,
+            (false, true) => matrix.false_negatives += 1,
+        }
+    }
+
+    matrix
+}
+
+/// Summary scores derived from a confusion matrix, precipitation being the positive class.
+///
+/// Ratios whose denominator is zero (e.g. precision when nothing is predicted positive) are 0.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ClassificationReport {
+    pub confusion_matrix: ConfusionMatrix,
+    pub accuracy: f32,
+    pub precision: f32,
+    pub recall: f32,
+    pub f1_score: f32,
+}
+
+impl ClassificationReport {
+    pub fn from_confusion_matrix(confusion_matrix: ConfusionMatrix) -> Self {
+        let ratio = |numerator: usize, denominator: usize| {
+            if denominator == 0 {
+                0.0
+            } else {
+                numerator as f32 / denominator as f32
+            }
+        };
+        let matrix = &confusion_matrix;
+
+        let precision = ratio(
+            matrix.true_positives,
+            matrix.true_positives + matrix.false_positives,
+        );
+        let recall = ratio(
+            matrix.true_positives,
+            matrix.true_positives + matrix.false_negatives,
+        );
+        let f1_score = if precision + recall == 0.0 {
+            0.0
+        } else {
+            2.0 * precision * recall / (precision + recall)
+        };
+
+        ClassificationReport {
+            confusion_matrix,
+            accuracy: ratio(
+                matrix.true_positives + matrix.true_negatives,
+                matrix.total(),
+            ),
+            precision,
+            recall,
+            f1_score,
+        }
+    }
+}
+
+/// Classification report at `threshold`, an output exactly at `threshold` counting as positive.
+pub fn classification_report(
+    network: &NeuralNetwork,
+    inputs: &[Vec<f32>],
+    targets: &[Vec<f32>],
+    threshold: f32,
+) -> ClassificationReport {
+    classification_report_with_bound(network, inputs, targets, threshold, InclusiveBound::AtLeast)
+}
+
+/// `classification_report` with an explicit `bound` for outputs exactly at `threshold`.
+pub fn classification_report_with_bound(
+    network: &NeuralNetwork,
+    inputs: &[Vec<f32>],
+    targets: &[Vec<f32>],
+    threshold: f32,
+    bound: InclusiveBound,
+) -> ClassificationReport {
+    ClassificationReport::from_confusion_matrix(confusion_matrix_with_bound(
+        network, inputs, targets, threshold, bound,
+    ))
+}
+
+/// Matthews correlation coefficient of the report's confusion matrix, in `[-1, 1]`.
+///
+/// Returns 0.0 when any row or column of the matrix is empty.
+pub fn matthews_corrcoef(report: &ClassificationReport) -> f32 {
+    let matrix = &report.confusion_matrix;
+    let tp = matrix.true_positives as f64;
+    let tn = matrix.true_negatives as f64;
+    let fp = matrix.false_positives as f64;
+    let fn_ = matrix.false_negatives as f64;
+
+    let denominator = ((tp + fp) * (tp + fn_) * (tn + fp) * (tn + fn_)).sqrt();
+    if denominator == 0.0 {
+        return 0.0;
+    }
+
+    ((tp * tn - fp * fn_) / denominator) as f32
+}
+
+/// Mean negative log-likelihood `-(t ln p + (1 - t) ln(1 - p))` of the targets.
+///
+/// Probabilities are clamped away from 0 and 1 so a confident mistake costs a large but
+/// finite loss.
+pub fn log_loss(network: &NeuralNetwork, inputs: &[Vec<f32>], targets: &[Vec<f32>]) -> f32 {
+    network.compute_loss(inputs, targets, LossFunction::BinaryCrossEntropy)
+}
+
+/// Accuracy over `data` together with every data point the network misclassifies.
+///
+/// A probability exactly at `threshold` counts as positive. The accuracy of an empty
+/// `data` is 0.0.
+pub fn evaluate_with_errors<'a>(
+    network: &NeuralNetwork,
+    data: &'a [SimplifiedWeatherDataPoint],
+    threshold: f32,
+) -> (f32, Vec<&'a SimplifiedWeatherDataPoint>) {
+    let errors: Vec<&SimplifiedWeatherDataPoint> = data
+        .iter()
+        .filter(|data_point| {
+            let probability = network.predict_proba(&data_point.input.to_vec());
+            let predicted = InclusiveBound::AtLeast.is_positive(probability, threshold);
+            predicted != data_point.output
+        })
+        .collect();
+
+    if data.is_empty() {
+        return (0.0, errors);
+    }
+
+    let accuracy = (data.len() - errors.len()) as f32 / data.len() as f32;
+    (accuracy, errors)
+}
+
+/// Permutation feature importance of the four weather features.
+///
+/// For each feature, its column is shuffled across samples (with a fixed seed, so
+/// results are reproducible) and the importance is the resulting drop in accuracy
+/// compared to the unshuffled baseline.
+pub fn permutation_importance(
+    network: &NeuralNetwork,
+    inputs: &[Vec<f32>],
+    targets: &[Vec<f32>],
+) -> [f32; 4] {
+    let threshold = 0.5;
+    let baseline = binary_accuracy(network, inputs, targets, threshold);
+    let mut rng = StdRng::seed_from_u64(PERMUTATION_SEED);
+    let mut importances = [0.0; 4];
+
+    for (feature_idx, importance) in importances.iter_mut().enumerate() {
+        let mut column: Vec<f32> = inputs.iter().map(|input| input[feature_idx]).collect();
+        column.shuffle(&mut rng);
+
+        let permuted_inputs: Vec<Vec<f32>> = inputs
+            .iter()
+            .zip(&column)
+            .map(|(input, &value)| {
+                let mut permuted = input.clone();
+                permuted[feature_idx] = value;
+                permuted
+            })
+            .collect();
+
+        *importance = baseline - binary_accuracy(network, &permuted_inputs, targets, threshold);
+    }
+
+    importances
+}
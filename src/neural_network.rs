@@ -1,4 +1,10 @@
+use crate::activation::ActivationFunction;
+use crate::initializer::InitScheme;
 use crate::layer::Layer;
+use crate::neuron::Neuron;
+use num_traits::Float;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
 
 /// Represents a neural network composed of multiple layers.
@@ -6,6 +12,14 @@ use serde::{Deserialize, Serialize};
 /// ## Fields
 /// - `layers`: A vector of `Layer` objects that make up the neural network.
 ///
+/// The network is generic over its float type `T` and defaults to `f32`, which is the
+/// precision used by the training code. An `f64` network supports the same construction
+/// and inference methods:
+///
+/// ```rust
+/// let network: NeuralNetwork<f64> = NeuralNetwork::new(vec![layer]);
+/// ```
+///
 /// ## Methods
 ///
 /// ### `new`
@@ -91,6 +105,22 @@ use serde::{Deserialize, Serialize};
 ///
 /// -------------------------------------
 ///
+/// ### `activate_final`
+/// Propagates the inputs through all layers like `activate`, but only keeps the output of
+/// the last layer instead of collecting every intermediate output. Prefer it for inference.
+///
+/// #### Parameters:
+/// - `inputs`: A slice of input values to feed into the network.
+///
+/// ```rust
+/// let outputs = network.activate_final(&[1.0, 2.0]);
+/// println!("Outputs: {:?}", outputs);
+/// ```
+/// #### Returns:
+/// The outputs of the last layer.
+///
+/// -------------------------------------
+///
 /// ### `get_layer_count`
 /// Retrieves the number of layers in the neural network.
 ///
@@ -170,17 +200,248 @@ use serde::{Deserialize, Serialize};
 ///     }
 /// });
 /// ```
+///
+/// -------------------------------------
+///
+/// ### `reinitialize`
+/// Redraws every neuron's weights and bias in place, keeping the architecture,
+/// IDs, names and activation functions untouched.
+///
+/// #### Parameters:
+/// - `scheme`: The `InitScheme` used to draw the new weights.
+/// - `seed`: The seed of the random generator, so that a reinitialization is reproducible.
+///
+/// ```rust
+/// network.reinitialize(InitScheme::HeNormal, 42);
+/// ```
+///
+/// -------------------------------------
+///
+/// ### `predict_proba`
+/// Runs the inputs through the network and returns the first output of the last layer,
+/// i.e. the probability of precipitation for the binary weather classifier.
+///
+/// #### Parameters:
+/// - `inputs`: A slice of (normalized) input values to feed into the network.
+///
+/// ```rust
+/// let probability = network.predict_proba(&[0.5, 0.3, 0.2, 0.8]);
+/// ```
+/// #### Returns:
+/// The output probability.
+///
+/// -------------------------------------
+///
+/// ### `predict_proba_batch`
+/// Computes `predict_proba` for every input, reusing the same two layer buffers for the
+/// whole batch instead of allocating per input and per layer.
+///
+/// #### Parameters:
+/// - `inputs`: The (normalized) inputs, one per sample.
+///
+/// ```rust
+/// let probabilities = network.predict_proba_batch(&[vec![0.5, 0.3, 0.2, 0.8], vec![0.1, 0.9, 0.4, 0.2]]);
+/// ```
+/// #### Returns:
+/// One probability per input, in order.
+///
+/// -------------------------------------
+///
+/// ### `prediction_entropy`
+/// Computes the binary entropy `-p*log2(p) - (1-p)*log2(1-p)` of the output probability.
+/// It is close to 1 when the network is uncertain (p near 0.5) and close to 0 when it is
+/// confident; `0 * log2(0)` is treated as 0 so that p = 0 and p = 1 are handled.
+///
+/// #### Parameters:
+/// - `inputs`: A slice of (normalized) input values to feed into the network.
+///
+/// ```rust
+/// let entropy = network.prediction_entropy(&[0.5, 0.3, 0.2, 0.8]);
+/// println!("Entropy: {}", entropy);
+/// ```
+/// #### Returns:
+/// The entropy of the prediction, in bits.
+///
+/// -------------------------------------
+///
+/// ### `predict_topk`
+/// Ranks the classes of a multi-class network by the softmax of its outputs, which are
+/// treated as logits, so the output neurons are expected to be linear.
+///
+/// #### Parameters:
+/// - `inputs`: A slice of (normalized) input values to feed into the network.
+/// - `k`: How many classes to keep; all of them when `k` exceeds the output count.
+///
+/// ```rust
+/// for (class, probability) in network.predict_topk(&[0.5, 0.3, 0.2, 0.8], 2) {
+///     println!("Classe {}: {:.2}", class, probability);
+/// }
+/// ```
+/// #### Returns:
+/// The `(class index, probability)` pairs of the `k` most probable classes, most probable
+/// first, ties keeping the lower index first.
+///
+/// -------------------------------------
+///
+/// ### `prune`
+/// Sets to exactly zero every weight whose absolute value is below the threshold.
+/// Weights are zeroed rather than removed so that every weight vector keeps its length.
+/// Biases are left untouched.
+///
+/// #### Parameters:
+/// - `threshold`: The magnitude under which a weight is considered negligible.
+///
+/// ```rust
+/// let pruned = network.prune(1e-3);
+/// println!("Pruned {} weights", pruned);
+/// ```
+/// #### Returns:
+/// The number of non-zero weights that were pruned.
+///
+/// -------------------------------------
+///
+/// ### `activation_report`
+/// Runs every input through the network and summarizes the activations of each layer,
+/// which helps spotting dead ReLU neurons.
+///
+/// #### Parameters:
+/// - `inputs`: The input vectors to run through the network.
+///
+/// ```rust
+/// for stats in network.activation_report(&inputs) {
+///     println!("Layer {}: {:.0}% dead", stats.layer_id, stats.dead_fraction * 100.0);
+/// }
+/// ```
+/// #### Returns:
+/// One `LayerActStats` per layer, or an empty vector when `inputs` is empty.
+///
+/// -------------------------------------
+///
+/// ### `grow_layer`
+/// Appends randomly initialized neurons to a layer while keeping every learned weight.
+/// The neurons of the following layer get one small random weight per added neuron so
+/// that their weight vectors stay consistent with the grown layer.
+///
+/// #### Parameters:
+/// - `layer_id`: The ID of the layer to grow.
+/// - `extra_neurons`: The number of neurons to add.
+/// - `seed`: The seed of the random generator used for the new weights.
+///
+/// ```rust
+/// network.grow_layer(0, 4, 42);
+/// ```
+///
+/// -------------------------------------
+///
+/// ### `parameter_count`
+/// Retrieves the total number of weights and biases in the network.
+///
+/// ```rust
+/// println!("Parameters: {}", network.parameter_count());
+/// ```
+/// #### Returns:
+/// The number of trainable parameters.
+///
+/// -------------------------------------
+///
+/// ### `get_parameters`
+/// Flattens every parameter into a single vector, layer by layer and neuron by neuron,
+/// each neuron contributing its weights followed by its bias.
+///
+/// ```rust
+/// let parameters = network.get_parameters();
+/// ```
+/// #### Returns:
+/// A vector of `parameter_count()` values.
+///
+/// -------------------------------------
+///
+/// ### `set_parameters`
+/// Reloads parameters flattened in the order of `get_parameters`.
+///
+/// #### Parameters:
+/// - `parameters`: The flat parameters, exactly `parameter_count()` of them.
+///
+/// ```rust
+/// network.set_parameters(&parameters)?;
+/// ```
+/// #### Returns:
+/// An error, leaving the network untouched, if the length does not match `parameter_count()`.
+///
+/// -------------------------------------
+///
+/// ### `clip_parameters`
+/// Clamps every weight and bias to `[-limit, limit]`.
+///
+/// #### Parameters:
+/// - `limit`: The largest absolute value a parameter may keep. It must be finite and strictly
+///   positive: a negative limit would collapse every parameter onto it and a NaN one would
+///   disable the clamp.
+///
+/// ```rust
+/// network.clip_parameters(0.01);
+/// ```
+///
+/// -------------------------------------
+///
+/// ### `depth`
+/// Retrieves the number of layers, the same as `get_layer_count`.
+///
+/// ```rust
+/// println!("Depth: {}", network.depth());
+/// ```
+/// #### Returns:
+/// The number of layers in the network.
+///
+/// -------------------------------------
+///
+/// ### `max_width`
+/// Retrieves the largest neuron count across layers.
+///
+/// ```rust
+/// println!("Width: {}", network.max_width());
+/// ```
+/// #### Returns:
+/// The neuron count of the widest layer, or 0 for an empty network.
+///
+/// -------------------------------------
+///
+/// ### `lipschitz_bound`
+/// Estimates how much the outputs can change per unit change of the inputs, as the product
+/// over layers of the largest `lipschitz_constant * ||weights||` among the layer's neurons.
+/// The max row norm is a cheap stand-in for the spectral norm of each weight matrix, and
+/// never exceeds it, so the result is an estimate that can underestimate the true constant.
+///
+/// ```rust
+/// println!("Lipschitz: {}", network.lipschitz_bound());
+/// ```
+/// #### Returns:
+/// The estimated Lipschitz constant, 1 for an empty network.
+///
+/// -------------------------------------
+///
+/// ### `weight_diff`
+/// Measures how far the parameters of two networks with the same architecture are apart.
+///
+/// #### Parameters:
+/// - `other`: The network to compare against, e.g. an earlier checkpoint.
+///
+/// ```rust
+/// let distance = network.weight_diff(&checkpoint)?;
+/// ```
+/// #### Returns:
+/// The L2 distance between the flattened weights and biases, or an error if the architectures differ.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct NeuralNetwork {
-    pub layers: Vec<Layer>,
+pub struct NeuralNetwork<T = f32> {
+    pub layers: Vec<Layer<T>>,
 }
 
-impl NeuralNetwork {
-    pub fn new(layers: Vec<Layer>) -> Self {
+impl<T: Float> NeuralNetwork<T> {
+    pub fn new(layers: Vec<Layer<T>>) -> Self {
         NeuralNetwork { layers }
     }
 
-    pub fn add_layer(&mut self, layer: Layer) {
+    pub fn add_layer(&mut self, layer: Layer<T>) {
         self.layers.push(layer);
     }
 
@@ -188,15 +449,15 @@ impl NeuralNetwork {
         self.layers.retain(|layer| layer.id != layer_id);
     }
 
-    pub fn get_layer(&self, layer_id: u32) -> Option<&Layer> {
+    pub fn get_layer(&self, layer_id: u32) -> Option<&Layer<T>> {
         self.layers.iter().find(|&layer| layer.id == layer_id)
     }
 
-    pub fn get_layer_mut(&mut self, layer_id: u32) -> Option<&mut Layer> {
+    pub fn get_layer_mut(&mut self, layer_id: u32) -> Option<&mut Layer<T>> {
         self.layers.iter_mut().find(|layer| layer.id == layer_id)
     }
 
-    pub fn activate(&self, inputs: &[f32]) -> Vec<Vec<f32>> {
+    pub fn activate(&self, inputs: &[T]) -> Vec<Vec<T>> {
         let mut outputs = Vec::new();
         let mut current_inputs = inputs.to_vec();
 
@@ -209,6 +470,16 @@ impl NeuralNetwork {
         outputs
     }
 
+    pub fn activate_final(&self, inputs: &[T]) -> Vec<T> {
+        let mut current_inputs = inputs.to_vec();
+
+        for layer in &self.layers {
+            current_inputs = layer.activate(&current_inputs);
+        }
+
+        current_inputs
+    }
+
     pub fn get_layer_count(&self) -> usize {
         self.layers.len()
     }
@@ -221,16 +492,16 @@ impl NeuralNetwork {
         self.layers.iter().map(|layer| layer.name.clone()).collect()
     }
 
-    pub fn get_layer_neuron_details<T, F>(&self, extractor: F) -> Vec<T>
+    pub fn get_layer_neuron_details<D, F>(&self, extractor: F) -> Vec<D>
     where
-        F: Fn(&Layer) -> T,
+        F: Fn(&Layer<T>) -> D,
     {
         self.layers.iter().map(extractor).collect()
     }
 
     pub fn set_layer_property<F>(&mut self, layer_id: u32, setter: F)
     where
-        F: FnOnce(&mut Layer),
+        F: FnOnce(&mut Layer<T>),
     {
         if let Some(layer) = self.get_layer_mut(layer_id) {
             setter(layer);
@@ -239,10 +510,319 @@ impl NeuralNetwork {
 
     pub fn set_layer_neuron_property<F>(&mut self, layer_id: u32, neuron_id: u32, setter: F)
     where
-        F: FnOnce(&mut Layer, u32),
+        F: FnOnce(&mut Layer<T>, u32),
     {
         if let Some(layer) = self.get_layer_mut(layer_id) {
             setter(layer, neuron_id);
         }
     }
+
+    pub fn reinitialize(&mut self, scheme: InitScheme, seed: u64) {
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        for layer in &mut self.layers {
+            let fan_out = layer.neurons.len();
+
+            for neuron in &mut layer.neurons {
+                let fan_in = neuron.weights.len();
+
+                for weight in &mut neuron.weights {
+                    *weight = T::from(scheme.sample_weight(fan_in, fan_out, &mut rng)).unwrap();
+                }
+                neuron.bias = T::from(scheme.sample_bias(&mut rng)).unwrap();
+            }
+        }
+    }
+
+    pub fn predict_proba(&self, inputs: &[T]) -> T {
+        self.activate_final(inputs)[0]
+    }
+
+    pub fn predict_proba_batch(&self, inputs: &[Vec<T>]) -> Vec<T> {
+        let mut current = Vec::new();
+        let mut next = Vec::new();
+
+        inputs
+            .iter()
+            .map(|input| {
+                current.clear();
+                current.extend_from_slice(input);
+
+                for layer in &self.layers {
+                    next.clear();
+                    next.extend(layer.neurons.iter().map(|neuron| neuron.activate(&current)));
+                    std::mem::swap(&mut current, &mut next);
+                }
+
+                current[0]
+            })
+            .collect()
+    }
+
+    pub fn prediction_entropy(&self, inputs: &[T]) -> T {
+        let probability = self.predict_proba(inputs);
+        let term = |p: T| {
+            if p <= T::zero() {
+                T::zero()
+            } else {
+                -p * p.log2()
+            }
+        };
+
+        term(probability) + term(T::one() - probability)
+    }
+
+    pub fn predict_topk(&self, inputs: &[T], k: usize) -> Vec<(usize, T)> {
+        let logits = self.activate_final(inputs);
+        // Shifting by the largest logit keeps every exponential in (0, 1].
+        let max_logit = logits
+            .iter()
+            .fold(T::neg_infinity(), |max, &logit| max.max(logit));
+        let exponentials: Vec<T> = logits
+            .iter()
+            .map(|&logit| (logit - max_logit).exp())
+            .collect();
+        let total = exponentials.iter().fold(T::zero(), |sum, &e| sum + e);
+
+        let mut ranked: Vec<(usize, T)> = exponentials
+            .into_iter()
+            .map(|e| e / total)
+            .enumerate()
+            .collect();
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        ranked.truncate(k);
+
+        ranked
+    }
+
+    pub fn prune(&mut self, threshold: T) -> usize {
+        let mut pruned = 0;
+
+        for layer in &mut self.layers {
+            for neuron in &mut layer.neurons {
+                for weight in &mut neuron.weights {
+                    if weight.abs() < threshold && !weight.is_zero() {
+                        *weight = T::zero();
+                        pruned += 1;
+                    }
+                }
+            }
+        }
+
+        pruned
+    }
+
+    pub fn activation_report(&self, inputs: &[Vec<T>]) -> Vec<LayerActStats<T>> {
+        if inputs.is_empty() {
+            return Vec::new();
+        }
+
+        let mut always_zero: Vec<Vec<bool>> = self
+            .layers
+            .iter()
+            .map(|layer| vec![true; layer.neurons.len()])
+            .collect();
+        let mut sums = vec![T::zero(); self.layers.len()];
+        let mut maxima = vec![T::neg_infinity(); self.layers.len()];
+
+        for input in inputs {
+            for (layer_idx, outputs) in self.activate(input).iter().enumerate() {
+                for (neuron_idx, &output) in outputs.iter().enumerate() {
+                    if !output.is_zero() {
+                        always_zero[layer_idx][neuron_idx] = false;
+                    }
+                    sums[layer_idx] = sums[layer_idx] + output;
+                    maxima[layer_idx] = maxima[layer_idx].max(output);
+                }
+            }
+        }
+
+        self.layers
+            .iter()
+            .enumerate()
+            .map(|(layer_idx, layer)| {
+                let neuron_count = layer.neurons.len();
+                let dead_count = always_zero[layer_idx].iter().filter(|&&dead| dead).count();
+                let activation_count = T::from(neuron_count * inputs.len()).unwrap();
+
+                LayerActStats {
+                    layer_id: layer.id,
+                    dead_fraction: T::from(dead_count).unwrap()
+                        / T::from(neuron_count.max(1)).unwrap(),
+                    mean_activation: sums[layer_idx] / activation_count.max(T::one()),
+                    max_activation: maxima[layer_idx],
+                }
+            })
+            .collect()
+    }
+
+    pub fn grow_layer(&mut self, layer_id: u32, extra_neurons: usize, seed: u64) {
+        let Some(layer_idx) = self.layers.iter().position(|layer| layer.id == layer_id) else {
+            return;
+        };
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let layer = &mut self.layers[layer_idx];
+        let fan_in = layer
+            .neurons
+            .first()
+            .map_or(0, |neuron| neuron.weights.len());
+        let fan_out = layer.neurons.len() + extra_neurons;
+        let activation_function = layer
+            .neurons
+            .last()
+            .map_or(ActivationFunction::Relu, |neuron| {
+                neuron.activation_function
+            });
+        let first_id = layer
+            .neurons
+            .iter()
+            .map(|neuron| neuron.id + 1)
+            .max()
+            .unwrap_or(0);
+
+        for neuron_id in first_id..first_id + extra_neurons as u32 {
+            let weights = (0..fan_in)
+                .map(|_| {
+                    T::from(InitScheme::XavierUniform.sample_weight(fan_in, fan_out, &mut rng))
+                        .unwrap()
+                })
+                .collect();
+            let bias = T::from(InitScheme::XavierUniform.sample_bias(&mut rng)).unwrap();
+
+            layer.add_neuron(Neuron::new(
+                neuron_id,
+                format!("{}_{}", layer.name, neuron_id),
+                activation_function,
+                bias,
+                weights,
+            ));
+        }
+
+        if let Some(next_layer) = self.layers.get_mut(layer_idx + 1) {
+            for neuron in &mut next_layer.neurons {
+                for _ in 0..extra_neurons {
+                    neuron
+                        .weights
+                        .push(T::from(rng.random_range(-0.01..0.01)).unwrap());
+                }
+            }
+        }
+    }
+
+    pub fn parameter_count(&self) -> usize {
+        self.layers
+            .iter()
+            .flat_map(|layer| &layer.neurons)
+            .map(|neuron| neuron.weights.len() + 1)
+            .sum()
+    }
+
+    pub fn get_parameters(&self) -> Vec<T> {
+        let mut parameters = Vec::with_capacity(self.parameter_count());
+
+        for neuron in self.layers.iter().flat_map(|layer| &layer.neurons) {
+            parameters.extend_from_slice(&neuron.weights);
+            parameters.push(neuron.bias);
+        }
+
+        parameters
+    }
+
+    pub fn set_parameters(&mut self, parameters: &[T]) -> Result<(), String> {
+        let expected = self.parameter_count();
+        if parameters.len() != expected {
+            return Err(format!(
+                "Attendu {} paramètres, reçu {}",
+                expected,
+                parameters.len()
+            ));
+        }
+
+        let mut offset = 0;
+        for neuron in self.layers.iter_mut().flat_map(|layer| &mut layer.neurons) {
+            let weight_count = neuron.weights.len();
+            neuron
+                .weights
+                .copy_from_slice(&parameters[offset..offset + weight_count]);
+            neuron.bias = parameters[offset + weight_count];
+            offset += weight_count + 1;
+        }
+
+        Ok(())
+    }
+
+    pub fn clip_parameters(&mut self, limit: T) {
+        for neuron in self.layers.iter_mut().flat_map(|layer| &mut layer.neurons) {
+            for weight in &mut neuron.weights {
+                *weight = weight.max(-limit).min(limit);
+            }
+            neuron.bias = neuron.bias.max(-limit).min(limit);
+        }
+    }
+
+    pub fn weight_diff(&self, other: &NeuralNetwork<T>) -> Result<T, String> {
+        let shape = |network: &NeuralNetwork<T>| -> Vec<Vec<usize>> {
+            network.get_layer_neuron_details(|layer| {
+                layer
+                    .neurons
+                    .iter()
+                    .map(|neuron| neuron.weights.len())
+                    .collect()
+            })
+        };
+        if shape(self) != shape(other) {
+            return Err("Les architectures des deux réseaux diffèrent".to_string());
+        }
+
+        let squared_distance = self
+            .get_parameters()
+            .into_iter()
+            .zip(other.get_parameters())
+            .fold(T::zero(), |sum, (a, b)| sum + (a - b) * (a - b));
+
+        Ok(squared_distance.sqrt())
+    }
+
+    pub fn depth(&self) -> usize {
+        self.get_layer_count()
+    }
+
+    pub fn max_width(&self) -> usize {
+        self.get_layer_neuron_details(|layer| layer.neurons.len())
+            .into_iter()
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn lipschitz_bound(&self) -> T {
+        // The max row norm is a lower bound on the spectral norm, so this can underestimate.
+        self.layers.iter().fold(T::one(), |bound, layer| {
+            let layer_bound = layer.neurons.iter().fold(T::zero(), |max, neuron| {
+                let row_norm = neuron
+                    .weights
+                    .iter()
+                    .fold(T::zero(), |sum, &w| sum + w * w)
+                    .sqrt();
+                let slope = T::from(neuron.activation_function.lipschitz_constant()).unwrap();
+                max.max(slope * row_norm)
+            });
+            bound * layer_bound
+        })
+    }
+}
+
+/// Activation statistics of one layer over a set of inputs, see `NeuralNetwork::activation_report`.
+///
+/// ## Fields
+/// - `layer_id`: The ID of the layer.
+/// - `dead_fraction`: The fraction of the layer's neurons whose output was zero for every input.
+/// - `mean_activation`: The mean output over all neurons and inputs.
+/// - `max_activation`: The largest output seen in the layer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayerActStats<T = f32> {
+    pub layer_id: u32,
+    pub dead_fraction: T,
+    pub mean_activation: T,
+    pub max_activation: T,
 }
@@ -1,3 +1,5 @@
+use crate::activation::ActivationFunction;
+use num_traits::Float;
 use serde::{Deserialize, Serialize};
 
 /// Represents a neuron in a neural network.
@@ -5,26 +7,45 @@ use serde::{Deserialize, Serialize};
 /// ## Fields
 /// - `id`: A unique identifier for the neuron.
 /// - `name`: The name of the neuron.
-/// - `activation_function`: The activation function used by the neuron (e.g., "sigmoid", "relu").
+/// - `activation_function`: The `ActivationFunction` used by the neuron (e.g., `Sigmoid`, `Relu`).
 /// - `bias`: The bias value added to the weighted sum of inputs.
 /// - `weights`: The weights associated with the inputs to the neuron.
+/// - `use_bias`: Whether the bias is added and trained; `true` by default, including for saved models
+///   that predate the field.
+///
+/// ## Precision
+/// The neuron is generic over its float type `T` (any `num_traits::Float`), `f32` by default.
+/// `Neuron<f64>` can be used where the extra precision matters, e.g. for gradient checking.
 ///
 /// ## Methods
 ///
 /// ### `new`
 ///  ``` rust
-///     let neuron = Neuron::new(1, "Neuron1".to_string(), "sigmoid".to_string(), 0.5, vec![0.2, 0.3]);
+///     let neuron = Neuron::new(1, "Neuron1".to_string(), ActivationFunction::Sigmoid, 0.5, vec![0.2, 0.3]);
 /// ```   
 ///
 /// -------------------------------------
 ///
+/// ### `try_new`
+/// Creates a neuron from the name of its activation function.
+///
+/// #### Parameters:
+/// - `activation_function`: The activation name, e.g. "sigmoid" or "relu".
+/// ``` rust
+/// let neuron = Neuron::try_new(1, "Neuron1".to_string(), "sigmoid", 0.5, vec![0.2, 0.3])?;
+/// ```
+/// #### Returns:
+/// The neuron, or an error naming the activation if it is unknown.
+///
+/// -------------------------------------
+///
 /// ### `activate`
 /// Computes the output of the neuron given a set of inputs.
 ///
 /// #### Parameters:
 /// - `inputs`: A slice of input values to the neuron.
 /// ``` rust
-/// let neuron = Neuron::new(1, "Neuron1".to_string(), "sigmoid".to_string(), 0.5, vec![0.2, 0.3]);
+/// let neuron = Neuron::new(1, "Neuron1".to_string(), ActivationFunction::Sigmoid, 0.5, vec![0.2, 0.3]);
 /// let inputs = vec![1.0, 2.0];
 /// let output = neuron.activate(&inputs);
 /// println!("Output: {}", output);
@@ -41,29 +62,47 @@ use serde::{Deserialize, Serialize};
 /// - `value`: The input value to the activation function.
 ///
 /// ``` rust
-/// let neuron = Neuron::new(1, "Neuron1".to_string(), "sigmoid".to_string(), 0.5, vec![0.2, 0.3]);
+/// let neuron = Neuron::new(1, "Neuron1".to_string(), ActivationFunction::Sigmoid, 0.5, vec![0.2, 0.3]);
 /// let value = 0.5;
 /// let activated_value = neuron.apply_activation_function(value);
 /// println!("Activated Value: {}", activated_value);
 /// ```
 /// #### Returns:
-/// The result of applying the activation function. Defaults to linear if the activation function is unknown.
+/// The result of applying the activation function.
+///
+/// -------------------------------------
+///
+/// ### `effective_bias`
+/// The bias actually added to the weighted sum.
+///
+/// ``` rust
+/// neuron.use_bias = false;
+/// assert_eq!(neuron.effective_bias(), 0.0);
+/// ```
+/// #### Returns:
+/// `bias`, or zero when `use_bias` is `false`.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Neuron {
+pub struct Neuron<T = f32> {
     pub id: u32,
     pub name: String,
-    pub activation_function: String,
-    pub bias: f32,
-    pub weights: Vec<f32>,
+    pub activation_function: ActivationFunction,
+    pub bias: T,
+    pub weights: Vec<T>,
+    #[serde(default = "default_use_bias")]
+    pub use_bias: bool,
+}
+
+fn default_use_bias() -> bool {
+    true
 }
 
-impl Neuron {
+impl<T: Float> Neuron<T> {
     pub fn new(
         id: u32,
         name: String,
-        activation_function: String,
-        bias: f32,
-        weights: Vec<f32>,
+        activation_function: ActivationFunction,
+        bias: T,
+        weights: Vec<T>,
     ) -> Self {
         Neuron {
             id,
@@ -71,19 +110,39 @@ impl Neuron {
             activation_function,
             bias,
             weights,
+            use_bias: true,
         }
     }
 
-    pub fn activate(&self, inputs: &[f32]) -> f32 {
-        let weighted_sum: f32 = inputs.iter().zip(&self.weights).map(|(x, w)| x * w).sum();
-        self.apply_activation_function(weighted_sum + self.bias)
+    pub fn try_new(
+        id: u32,
+        name: String,
+        activation_function: &str,
+        bias: T,
+        weights: Vec<T>,
+    ) -> Result<Self, String> {
+        Ok(Neuron::new(
+            id,
+            name,
+            activation_function.parse()?,
+            bias,
+            weights,
+        ))
+    }
+
+    pub fn activate(&self, inputs: &[T]) -> T {
+        let weighted_sum = inputs
+            .iter()
+            .zip(&self.weights)
+            .fold(T::zero(), |sum, (&x, &w)| sum + x * w);
+        self.apply_activation_function(weighted_sum + self.effective_bias())
     }
 
-    pub fn apply_activation_function(&self, value: f32) -> f32 {
-        match self.activation_function.as_str() {
-            "sigmoid" => 1.0 / (1.0 + (-value).exp()),
-            "relu" => value.max(0.0),
-            _ => value, // Default to linear if unknown
-        }
+    pub fn apply_activation_function(&self, value: T) -> T {
+        self.activation_function.apply(value)
+    }
+
+    pub fn effective_bias(&self) -> T {
+        if self.use_bias { self.bias } else { T::zero() }
     }
 }
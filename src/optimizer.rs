@@ -0,0 +1,238 @@
+use serde::{Deserialize, Serialize};
+
+/// Updates a flat parameter vector from its loss gradient.
+///
+/// Parameters and gradients are laid out as in `NeuralNetwork::get_parameters`. The
+/// gradient is `dL/dθ`, so implementations move the parameters against it.
+pub trait Optimizer {
+    fn step(&mut self, parameters: &mut [f32], gradients: &[f32], learning_rate: f32);
+}
+
+/// Plain gradient descent: `θ -= learning_rate * g`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SgdOptimizer;
+
+impl Optimizer for SgdOptimizer {
+    fn step(&mut self, parameters: &mut [f32], gradients: &[f32], learning_rate: f32) {
+        for (parameter, &gradient) in parameters.iter_mut().zip(gradients) {
+            *parameter -= learning_rate * gradient;
+        }
+    }
+}
+
+/// Gradient descent with momentum: `v = momentum * v + learning_rate * g`, then `θ -= v`.
+///
+/// With `nesterov` set, the update uses the look-ahead step `momentum * v + learning_rate * g`
+/// instead of `v`.
+///
+/// # Fields
+///
+/// * `momentum` - Fraction of the previous velocity kept at each step.
+/// * `nesterov` - Whether to apply the Nesterov correction.
+/// * `velocity` - Per-parameter velocity, sized on the first step.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MomentumOptimizer {
+    pub momentum: f32,
+    pub nesterov: bool,
+    pub velocity: Vec<f32>,
+}
+
+impl MomentumOptimizer {
+    pub fn new(momentum: f32, nesterov: bool) -> Self {
+        MomentumOptimizer {
+            momentum,
+            nesterov,
+            velocity: Vec::new(),
+        }
+    }
+}
+
+impl Optimizer for MomentumOptimizer {
+    fn step(&mut self, parameters: &mut [f32], gradients: &[f32], learning_rate: f32) {
+        if self.velocity.len() != parameters.len() {
+            self.velocity = vec![0.0; parameters.len()];
+        }
+
+        for ((parameter, &gradient), velocity) in
+            parameters.iter_mut().zip(gradients).zip(&mut self.velocity)
+        {
+            *velocity = self.momentum * *velocity + learning_rate * gradient;
+            *parameter -= if self.nesterov {
+                self.momentum * *velocity + learning_rate * gradient
+            } else {
+                *velocity
+            };
+        }
+    }
+}
+
+/// RMSProp: each parameter's step is scaled by a running average of its squared gradients.
+///
+/// # Fields
+///
+/// * `decay` - Weight of the previous average in the running mean, 0.9 by default.
+/// * `epsilon` - Added to the root mean square to avoid dividing by zero.
+/// * `squared_gradient_average` - Per-parameter running average, sized on the first step.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RmsPropOptimizer {
+    pub decay: f32,
+    pub epsilon: f32,
+    pub squared_gradient_average: Vec<f32>,
+}
+
+impl RmsPropOptimizer {
+    pub fn new(decay: f32, epsilon: f32) -> Self {
+        RmsPropOptimizer {
+            decay,
+            epsilon,
+            squared_gradient_average: Vec::new(),
+        }
+    }
+}
+
+impl Default for RmsPropOptimizer {
+    fn default() -> Self {
+        RmsPropOptimizer::new(0.9, 1e-8)
+    }
+}
+
+impl Optimizer for RmsPropOptimizer {
+    fn step(&mut self, parameters: &mut [f32], gradients: &[f32], learning_rate: f32) {
+        if self.squared_gradient_average.len() != parameters.len() {
+            self.squared_gradient_average = vec![0.0; parameters.len()];
+        }
+
+        for ((parameter, &gradient), average) in parameters
+            .iter_mut()
+            .zip(gradients)
+            .zip(&mut self.squared_gradient_average)
+        {
+            *average = self.decay * *average + (1.0 - self.decay) * gradient * gradient;
+            *parameter -= learning_rate * gradient / (average.sqrt() + self.epsilon);
+        }
+    }
+}
+
+/// AdamW: Adam with weight decay decoupled from the gradient.
+///
+/// The moments are the usual bias-corrected Adam averages `m_hat` and `v_hat`; each step is
+/// `θ -= learning_rate * (m_hat / (sqrt(v_hat) + epsilon) + weight_decay * θ)`, so with a zero
+/// gradient the parameters shrink by `1 - learning_rate * weight_decay` per step.
+///
+/// # Fields
+///
+/// * `beta1` - Decay of the first-moment (mean) average, 0.9 by default.
+/// * `beta2` - Decay of the second-moment (uncentered variance) average, 0.999 by default.
+/// * `epsilon` - Added to the root of the second moment to avoid dividing by zero.
+/// * `weight_decay` - Fraction of each parameter removed per unit of learning rate, 0.01 by default.
+/// * `first_moment` - Per-parameter first moment, sized on the first step.
+/// * `second_moment` - Per-parameter second moment, sized on the first step.
+/// * `step_count` - Number of steps taken, used for the bias correction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdamWOptimizer {
+    pub beta1: f32,
+    pub beta2: f32,
+    pub epsilon: f32,
+    pub weight_decay: f32,
+    pub first_moment: Vec<f32>,
+    pub second_moment: Vec<f32>,
+    pub step_count: i32,
+}
+
+impl AdamWOptimizer {
+    pub fn new(beta1: f32, beta2: f32, epsilon: f32, weight_decay: f32) -> Self {
+        AdamWOptimizer {
+            beta1,
+            beta2,
+            epsilon,
+            weight_decay,
+            first_moment: Vec::new(),
+            second_moment: Vec::new(),
+            step_count: 0,
+        }
+    }
+}
+
+impl Default for AdamWOptimizer {
+    fn default() -> Self {
+        AdamWOptimizer::new(0.9, 0.999, 1e-8, 0.01)
+    }
+}
+
+impl Optimizer for AdamWOptimizer {
+    fn step(&mut self, parameters: &mut [f32], gradients: &[f32], learning_rate: f32) {
+        if self.first_moment.len() != parameters.len() {
+            self.first_moment = vec![0.0; parameters.len()];
+            self.second_moment = vec![0.0; parameters.len()];
+            self.step_count = 0;
+        }
+
+        self.step_count += 1;
+        let first_correction = 1.0 - self.beta1.powi(self.step_count);
+        let second_correction = 1.0 - self.beta2.powi(self.step_count);
+
+        for (((parameter, &gradient), first), second) in parameters
+            .iter_mut()
+            .zip(gradients)
+            .zip(&mut self.first_moment)
+            .zip(&mut self.second_moment)
+        {
+            *first = self.beta1 * *first + (1.0 - self.beta1) * gradient;
+            *second = self.beta2 * *second + (1.0 - self.beta2) * gradient * gradient;
+
+            let first_hat = *first / first_correction;
+            let second_hat = *second / second_correction;
+            *parameter -= learning_rate
+                * (first_hat / (second_hat.sqrt() + self.epsilon) + self.weight_decay * *parameter);
+        }
+    }
+}
+
+/// The optimizer a model was trained with, as recorded in a `TrainingConfig`.
+///
+/// Only the hyperparameters are stored; `build` returns a fresh optimizer with empty state.
+///
+/// # Variants
+///
+/// * `Sgd` - `SgdOptimizer`.
+/// * `Momentum` - `MomentumOptimizer::new(momentum, nesterov)`.
+/// * `RmsProp` - `RmsPropOptimizer::new(decay, epsilon)`.
+/// * `AdamW` - `AdamWOptimizer::new(beta1, beta2, epsilon, weight_decay)`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum OptimizerKind {
+    Sgd,
+    Momentum {
+        momentum: f32,
+        nesterov: bool,
+    },
+    RmsProp {
+        decay: f32,
+        epsilon: f32,
+    },
+    AdamW {
+        beta1: f32,
+        beta2: f32,
+        epsilon: f32,
+        weight_decay: f32,
+    },
+}
+
+impl OptimizerKind {
+    pub fn build(&self) -> Box<dyn Optimizer> {
+        match *self {
+            OptimizerKind::Sgd => Box::new(SgdOptimizer),
+            OptimizerKind::Momentum { momentum, nesterov } => {
+                Box::new(MomentumOptimizer::new(momentum, nesterov))
+            }
+            OptimizerKind::RmsProp { decay, epsilon } => {
+                Box::new(RmsPropOptimizer::new(decay, epsilon))
+            }
+            OptimizerKind::AdamW {
+                beta1,
+                beta2,
+                epsilon,
+                weight_decay,
+            } => Box::new(AdamWOptimizer::new(beta1, beta2, epsilon, weight_decay)),
+        }
+    }
+}
@@ -1,6 +1,12 @@
+use crate::ensemble::Ensemble;
+use crate::loss::LossFunction;
 use crate::neural_network::NeuralNetwork;
+use crate::optimizer::OptimizerKind;
+use crate::trainer::{LrSchedule, TrainingConfig};
+use crate::weather_model::WeatherModel;
 use serde::{Deserialize, Serialize};
 use serde_json;
+use std::collections::HashMap;
 use std::fs::{File, OpenOptions};
 use std::io::{Read, Write};
 use std::path::Path;
@@ -9,6 +15,77 @@ use std::path::Path;
 pub struct SavedModel {
     pub network: NeuralNetwork,
     pub normalization_params: [f32; 8],
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub training_config: Option<TrainingConfig>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub metadata: HashMap<String, String>,
+    // The `WeatherModel` inference settings; files saved before they existed load as 1 and 0.
+    #[serde(default = "default_temperature")]
+    pub temperature: f32,
+    #[serde(default)]
+    pub dropout_rate: f32,
+}
+
+fn default_temperature() -> f32 {
+    1.0
+}
+
+// Everything `save_model_with_options` stores next to the network and its normalization.
+// The default has no configuration, no metadata, a temperature of 1 and no dropout.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SaveOptions {
+    pub training_config: Option<TrainingConfig>,
+    pub metadata: HashMap<String, String>,
+    pub temperature: f32,
+    pub dropout_rate: f32,
+}
+
+impl Default for SaveOptions {
+    fn default() -> Self {
+        SaveOptions {
+            training_config: None,
+            metadata: HashMap::new(),
+            temperature: default_temperature(),
+            dropout_rate: 0.0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SavedEnsemble {
+    pub models: Vec<SavedModel>,
+    pub weights: Vec<f32>,
+}
+
+// Only the flat parameters of a network, laid out as in `NeuralNetwork::get_parameters`,
+// plus the weight count of every neuron so the target architecture can be checked on load.
+#[derive(Serialize, Deserialize)]
+pub struct SavedWeights {
+    pub shape: Vec<Vec<usize>>,
+    pub parameters: Vec<f32>,
+}
+
+impl From<SavedModel> for WeatherModel {
+    fn from(saved_model: SavedModel) -> Self {
+        WeatherModel {
+            temperature: saved_model.temperature,
+            dropout_rate: saved_model.dropout_rate,
+            ..WeatherModel::new(saved_model.network, saved_model.normalization_params)
+        }
+    }
+}
+
+impl From<&WeatherModel> for SavedModel {
+    fn from(model: &WeatherModel) -> Self {
+        SavedModel {
+            network: model.network.clone(),
+            normalization_params: model.normalization_params,
+            training_config: None,
+            metadata: HashMap::new(),
+            temperature: model.temperature,
+            dropout_rate: model.dropout_rate,
+        }
+    }
 }
 
 pub fn save_model<P: AsRef<Path>>(
@@ -16,12 +93,295 @@ pub fn save_model<P: AsRef<Path>>(
     normalization_params: &[f32; 8],
     path: P,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let saved_model = SavedModel {
+    save_model_with_options(network, normalization_params, &SaveOptions::default(), path)
+}
+
+pub fn save_model_with_options<P: AsRef<Path>>(
+    network: &NeuralNetwork,
+    normalization_params: &[f32; 8],
+    options: &SaveOptions,
+    path: P,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let saved_model = saved_model_with_options(network, normalization_params, options);
+
+    ensure_finite(&saved_model)?;
+    write_json(&saved_model, path)
+}
+
+pub fn load_model<P: AsRef<Path>>(
+    path: P,
+) -> Result<(NeuralNetwork, [f32; 8]), Box<dyn std::error::Error>> {
+    load_model_from_bytes(&std::fs::read(path)?)
+}
+
+// Same as `load_model` for a model already in memory, e.g. embedded with `include_bytes!`.
+pub fn load_model_from_bytes(
+    bytes: &[u8],
+) -> Result<(NeuralNetwork, [f32; 8]), Box<dyn std::error::Error>> {
+    let saved_model = load_saved_model_from_bytes(bytes)?;
+
+    Ok((saved_model.network, saved_model.normalization_params))
+}
+
+// Same as `load_model`, but returns the whole saved model, training configuration and metadata
+// included; models saved without them load with `None` and an empty map.
+pub fn load_saved_model<P: AsRef<Path>>(path: P) -> Result<SavedModel, Box<dyn std::error::Error>> {
+    load_saved_model_from_bytes(&std::fs::read(path)?)
+}
+
+pub fn load_saved_model_from_bytes(bytes: &[u8]) -> Result<SavedModel, Box<dyn std::error::Error>> {
+    Ok(serde_json::from_slice(bytes)?)
+}
+
+// Same as `save_model`, as TOML meant to be read and edited by hand.
+pub fn save_model_toml<P: AsRef<Path>>(
+    network: &NeuralNetwork,
+    normalization_params: &[f32; 8],
+    path: P,
+) -> Result<(), Box<dyn std::error::Error>> {
+    save_model_toml_with_options(network, normalization_params, &SaveOptions::default(), path)
+}
+
+// Same contents as `save_model_with_options`, as TOML.
+pub fn save_model_toml_with_options<P: AsRef<Path>>(
+    network: &NeuralNetwork,
+    normalization_params: &[f32; 8],
+    options: &SaveOptions,
+    path: P,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let saved_model = saved_model_with_options(network, normalization_params, options);
+
+    ensure_finite(&saved_model)?;
+    std::fs::write(path, toml::to_string_pretty(&saved_model)?)?;
+    Ok(())
+}
+
+pub fn load_model_toml<P: AsRef<Path>>(
+    path: P,
+) -> Result<(NeuralNetwork, [f32; 8]), Box<dyn std::error::Error>> {
+    let saved_model = load_saved_model_toml(path)?;
+
+    Ok((saved_model.network, saved_model.normalization_params))
+}
+
+// Same as `load_saved_model` for a file written by `save_model_toml_with_options`.
+pub fn load_saved_model_toml<P: AsRef<Path>>(
+    path: P,
+) -> Result<SavedModel, Box<dyn std::error::Error>> {
+    Ok(toml::from_str(&std::fs::read_to_string(path)?)?)
+}
+
+pub fn save_weights<P: AsRef<Path>>(
+    network: &NeuralNetwork,
+    path: P,
+) -> Result<(), Box<dyn std::error::Error>> {
+    ensure_finite_network(network)?;
+    let saved_weights = SavedWeights {
+        shape: network_shape(network),
+        parameters: network.get_parameters(),
+    };
+
+    write_json(&saved_weights, path)
+}
+
+pub fn load_weights_into<P: AsRef<Path>>(
+    network: &mut NeuralNetwork,
+    path: P,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let saved_weights: SavedWeights = read_json(path)?;
+
+    if saved_weights.shape != network_shape(network) {
+        return Err("L'architecture du réseau ne correspond pas aux poids sauvegardés".into());
+    }
+
+    Ok(network.set_parameters(&saved_weights.parameters)?)
+}
+
+pub fn save_ensemble<P: AsRef<Path>>(
+    ensemble: &Ensemble,
+    path: P,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let saved_ensemble = SavedEnsemble {
+        models: ensemble.models.iter().map(SavedModel::from).collect(),
+        weights: ensemble.weights.clone(),
+    };
+
+    for saved_model in &saved_ensemble.models {
+        ensure_finite(saved_model)?;
+    }
+    if saved_ensemble
+        .weights
+        .iter()
+        .any(|weight| !weight.is_finite())
+    {
+        return Err("Les poids de l'ensemble contiennent une valeur non finie".into());
+    }
+    write_json(&saved_ensemble, path)
+}
+
+pub fn load_ensemble<P: AsRef<Path>>(path: P) -> Result<Ensemble, Box<dyn std::error::Error>> {
+    let saved_ensemble: SavedEnsemble = read_json(path)?;
+
+    if let Some(first) = saved_ensemble.models.first() {
+        let expected_width = input_width(&first.network);
+
+        for (i, saved_model) in saved_ensemble.models.iter().enumerate() {
+            if saved_model.normalization_params != first.normalization_params {
+                return Err(format!(
+                    "Le modèle {} de l'ensemble n'utilise pas la même normalisation",
+                    i
+                )
+                .into());
+            }
+            if input_width(&saved_model.network) != expected_width {
+                return Err(format!(
+                    "Le modèle {} de l'ensemble n'a pas la même largeur d'entrée",
+                    i
+                )
+                .into());
+            }
+        }
+    }
+
+    let models = saved_ensemble
+        .models
+        .into_iter()
+        .map(WeatherModel::from)
+        .collect();
+
+    Ok(Ensemble::with_weights(models, saved_ensemble.weights)?)
+}
+
+fn saved_model_with_options(
+    network: &NeuralNetwork,
+    normalization_params: &[f32; 8],
+    options: &SaveOptions,
+) -> SavedModel {
+    SavedModel {
         network: network.clone(),
         normalization_params: *normalization_params,
-    };
+        training_config: options.training_config.clone(),
+        metadata: options.metadata.clone(),
+        temperature: options.temperature,
+        dropout_rate: options.dropout_rate,
+    }
+}
+
+// JSON has no NaN or infinity: serde_json writes them as `null`, which cannot be read back into
+// an f32, so a diverged model would be saved but impossible to load.
+fn ensure_finite(saved_model: &SavedModel) -> Result<(), Box<dyn std::error::Error>> {
+    ensure_finite_network(&saved_model.network)?;
+
+    if saved_model
+        .normalization_params
+        .iter()
+        .any(|value| !value.is_finite())
+    {
+        return Err("Les paramètres de normalisation contiennent une valeur non finie".into());
+    }
+    if !saved_model.temperature.is_finite() || !saved_model.dropout_rate.is_finite() {
+        return Err("La température ou le taux de dropout n'est pas fini".into());
+    }
+    if let Some(config) = &saved_model.training_config
+        && training_config_floats(config)
+            .iter()
+            .any(|value| !value.is_finite())
+    {
+        return Err(
+            "La configuration d'entraînement contient une valeur non finie (NaN ou infini)".into(),
+        );
+    }
+
+    Ok(())
+}
+
+// Every f32 serialized with a `TrainingConfig`, nested schedule, loss, initializer and
+// optimizer parameters included.
+fn training_config_floats(config: &TrainingConfig) -> Vec<f32> {
+    let trainer = &config.trainer;
+    let mut values = vec![
+        trainer.learning_rate,
+        trainer.label_smoothing,
+        trainer.min_delta,
+        trainer.l1_activation_lambda,
+        config.init_config.bias_range.0,
+        config.init_config.bias_range.1,
+    ];
+    values.extend(trainer.weight_clip);
+    values.extend(trainer.normalization_params.iter().flatten());
+    values.extend(config.init_config.weight_scale);
+
+    match trainer.lr_schedule {
+        LrSchedule::Constant => {}
+        LrSchedule::Cyclical { base, max, .. } => values.extend([base, max]),
+        LrSchedule::CosineAnnealing { eta_min, .. } => values.push(eta_min),
+    }
+    if let LossFunction::FocalLoss { gamma } = trainer.loss_function {
+        values.push(gamma);
+    }
+    match config.optimizer {
+        None | Some(OptimizerKind::Sgd) => {}
+        Some(OptimizerKind::Momentum { momentum, .. }) => values.push(momentum),
+        Some(OptimizerKind::RmsProp { decay, epsilon }) => values.extend([decay, epsilon]),
+        Some(OptimizerKind::AdamW {
+            beta1,
+            beta2,
+            epsilon,
+            weight_decay,
+        }) => values.extend([beta1, beta2, epsilon, weight_decay]),
+    }
+
+    values
+}
 
-    let serialized = serde_json::to_string_pretty(&saved_model)?;
+fn ensure_finite_network(network: &NeuralNetwork) -> Result<(), Box<dyn std::error::Error>> {
+    for layer in &network.layers {
+        for neuron in &layer.neurons {
+            if neuron
+                .weights
+                .iter()
+                .chain([&neuron.bias])
+                .any(|value| !value.is_finite())
+            {
+                return Err(format!(
+                    "Le neurone {} de la couche {} a un poids ou un biais non fini (NaN ou infini), le modèle n'est pas sauvegardé",
+                    neuron.id, layer.id
+                )
+                .into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn network_shape(network: &NeuralNetwork) -> Vec<Vec<usize>> {
+    network
+        .layers
+        .iter()
+        .map(|layer| {
+            layer
+                .neurons
+                .iter()
+                .map(|neuron| neuron.weights.len())
+                .collect()
+        })
+        .collect()
+}
+
+fn input_width(network: &NeuralNetwork) -> Option<usize> {
+    network
+        .layers
+        .first()
+        .and_then(|layer| layer.neurons.first())
+        .map(|neuron| neuron.weights.len())
+}
+
+fn write_json<T: Serialize, P: AsRef<Path>>(
+    value: &T,
+    path: P,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let serialized = serde_json::to_string_pretty(value)?;
 
     let mut file = OpenOptions::new()
         .write(true)
@@ -34,14 +394,12 @@ pub fn save_model<P: AsRef<Path>>(
     Ok(())
 }
 
-pub fn load_model<P: AsRef<Path>>(
+fn read_json<T: for<'de> Deserialize<'de>, P: AsRef<Path>>(
     path: P,
-) -> Result<(NeuralNetwork, [f32; 8]), Box<dyn std::error::Error>> {
+) -> Result<T, Box<dyn std::error::Error>> {
     let mut file = File::open(path)?;
     let mut contents = String::new();
     file.read_to_string(&mut contents)?;
 
-    let saved_model: SavedModel = serde_json::from_str(&contents)?;
-
-    Ok((saved_model.network, saved_model.normalization_params))
+    Ok(serde_json::from_str(&contents)?)
 }
@@ -0,0 +1,134 @@
+use crate::activation::ActivationFunction;
+use crate::layer::Layer;
+use crate::neural_network::NeuralNetwork;
+use crate::neuron::Neuron;
+use serde::{Deserialize, Serialize};
+
+/// A neuron whose weights are stored as 8-bit integers.
+///
+/// The real weight is recovered as `(weight - zero_point) * scale`, with the scale and
+/// zero-point shared by every neuron of the layer. The bias is kept in full precision.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuantizedNeuron {
+    pub id: u32,
+    pub name: String,
+    pub activation_function: ActivationFunction,
+    pub bias: f32,
+    pub weights: Vec<i8>,
+}
+
+/// A layer quantized with a per-layer affine scale and zero-point.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuantizedLayer {
+    pub id: u32,
+    pub name: String,
+    pub scale: f32,
+    pub zero_point: i8,
+    pub neurons: Vec<QuantizedNeuron>,
+}
+
+/// An 8-bit quantized copy of a `NeuralNetwork`, about four times smaller to store.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuantizedNetwork {
+    pub layers: Vec<QuantizedLayer>,
+}
+
+pub fn quantize(network: &NeuralNetwork) -> QuantizedNetwork {
+    let layers = network.layers.iter().map(quantize_layer).collect();
+    QuantizedNetwork { layers }
+}
+
+fn quantize_layer(layer: &Layer) -> QuantizedLayer {
+    let weights = layer.neurons.iter().flat_map(|neuron| &neuron.weights);
+    // The range always contains 0 so that zero weights stay exactly representable.
+    let min = weights.clone().fold(0.0_f32, |min, &w| min.min(w));
+    let max = weights.fold(0.0_f32, |max, &w| max.max(w));
+
+    let scale = if max > min { (max - min) / 255.0 } else { 1.0 };
+    let zero_point = (-128.0 - min / scale).round().clamp(-128.0, 127.0) as i8;
+
+    let neurons = layer
+        .neurons
+        .iter()
+        .map(|neuron| QuantizedNeuron {
+            id: neuron.id,
+            name: neuron.name.clone(),
+            activation_function: neuron.activation_function,
+            bias: neuron.effective_bias(),
+            weights: neuron
+                .weights
+                .iter()
+                .map(|&w| (w / scale + zero_point as f32).round().clamp(-128.0, 127.0) as i8)
+                .collect(),
+        })
+        .collect();
+
+    QuantizedLayer {
+        id: layer.id,
+        name: layer.name.clone(),
+        scale,
+        zero_point,
+        neurons,
+    }
+}
+
+impl QuantizedLayer {
+    fn dequantize_weight(&self, weight: i8) -> f32 {
+        (weight as f32 - self.zero_point as f32) * self.scale
+    }
+
+    pub fn activate(&self, inputs: &[f32]) -> Vec<f32> {
+        self.neurons
+            .iter()
+            .map(|neuron| {
+                let weighted_sum: f32 = inputs
+                    .iter()
+                    .zip(&neuron.weights)
+                    .map(|(x, &w)| x * self.dequantize_weight(w))
+                    .sum();
+                neuron.activation_function.apply(weighted_sum + neuron.bias)
+            })
+            .collect()
+    }
+}
+
+impl QuantizedNetwork {
+    pub fn activate(&self, inputs: &[f32]) -> Vec<f32> {
+        let mut current_inputs = inputs.to_vec();
+
+        for layer in &self.layers {
+            current_inputs = layer.activate(&current_inputs);
+        }
+
+        current_inputs
+    }
+
+    pub fn dequantize(&self) -> NeuralNetwork {
+        let layers = self
+            .layers
+            .iter()
+            .map(|layer| {
+                let neurons = layer
+                    .neurons
+                    .iter()
+                    .map(|neuron| {
+                        Neuron::new(
+                            neuron.id,
+                            neuron.name.clone(),
+                            neuron.activation_function,
+                            neuron.bias,
+                            neuron
+                                .weights
+                                .iter()
+                                .map(|&w| layer.dequantize_weight(w))
+                                .collect(),
+                        )
+                    })
+                    .collect();
+                Layer::new(layer.id, layer.name.clone(), neurons)
+            })
+            .collect();
+
+        NeuralNetwork::new(layers)
+    }
+}
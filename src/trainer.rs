@@ -1,9 +1,22 @@
-use crate::back_propagation::NetworkExt;
-use crate::dataset_loader::{SimplifiedWeatherDataPoint, prepare_inputs, prepare_outputs};
+use crate::activation::ActivationFunction;
+use crate::back_propagation::{ForwardBuffers, L1ActivationPenalty, NetworkExt};
+use crate::dataset_loader::{
+    SimplifiedWeatherDataPoint, class_balance, prepare_inputs, prepare_outputs,
+};
+use crate::initializer::InitConfig;
 use crate::layer::Layer;
+use crate::loss::LossFunction;
+use crate::metrics::{InclusiveBound, binary_accuracy_with_bound};
 use crate::neural_network::NeuralNetwork;
 use crate::neuron::Neuron;
-use rand::Rng;
+use crate::optimizer::{Optimizer, OptimizerKind};
+use crate::pickle;
+use crate::weather_model::WeatherModel;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::path::{Path, PathBuf};
 
 /// A struct representing a binary classification trainer.
 ///
@@ -16,10 +29,161 @@ use rand::Rng;
 /// * `learning_rate` - The step size used for updating model parameters during training, maximally 1.0 and minimally 0.0.
 /// * `epochs` - The number of complete passes through the training dataset.
 /// * `batch_size` - The number of training samples used in one forward/backward pass.
+/// * `warmup_epochs` - Number of initial epochs over which the learning rate ramps linearly from 0, 0 (no warmup) by default.
+/// * `label_smoothing` - Amount `ε` the training targets are pulled towards each other: 1.0 becomes `1 - ε` and 0.0 becomes `ε`. 0 by default.
+/// * `checkpoint_path` - Where to save the network each time the monitored validation metric improves, `None` (no checkpoints) by default.
+/// * `normalization_params` - The normalization the training data was prepared with, saved with each checkpoint so it
+///   can be reloaded as a `WeatherModel`. Required when `checkpoint_path` is set, `None` by default.
+/// * `seed` - Seed for the per-epoch shuffling, making training reproducible; `None` (OS entropy) by default.
+/// * `loss_function` - The loss minimized during training and reported on the validation set, `MeanSquaredError` by default.
+/// * `monitor` - The validation metric early stopping watches, `StopMetric::Accuracy` by default.
+/// * `min_delta` - How much the monitored metric must improve to reset the patience, 0 by default.
+/// * `min_epochs` - Epochs without improvement are not counted against the patience before this
+///   (0-based) epoch, so a noisy warmup cannot stop training early. 0 by default.
+/// * `lr_schedule` - How the learning rate varies across epochs, `LrSchedule::Constant` by default.
+/// * `threshold_bound` - How an output exactly at the 0.5 threshold is classified when measuring accuracy,
+///   `InclusiveBound::AtLeast` (positive) by default.
+/// * `l1_activation_lambda` - Strength of the L1 penalty on the activations of the `l1_activation_layer` layer, 0 (off) by default.
+/// * `l1_activation_layer` - The id of the layer whose activations are penalized, 0 (the first hidden layer) by default.
+/// * `sampling` - How each epoch's sample order is drawn, `SamplingStrategy::Uniform` by default.
+/// * `weight_clip` - When set to `c`, every weight and bias is clamped to `[-c, c]` after each update
+///   (WGAN-style hard clipping); `c` must be finite and strictly positive. `None` (no clipping) by default.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct BinaryTrainer {
     pub learning_rate: f32,
     pub epochs: usize,
     pub batch_size: usize,
+    pub warmup_epochs: usize,
+    pub label_smoothing: f32,
+    pub checkpoint_path: Option<PathBuf>,
+    pub normalization_params: Option<[f32; 8]>,
+    pub seed: Option<u64>,
+    pub loss_function: LossFunction,
+    pub monitor: StopMetric,
+    pub min_delta: f32,
+    pub min_epochs: usize,
+    pub lr_schedule: LrSchedule,
+    pub threshold_bound: InclusiveBound,
+    pub l1_activation_lambda: f32,
+    pub l1_activation_layer: u32,
+    pub sampling: SamplingStrategy,
+    pub weight_clip: Option<f32>,
+}
+
+/// Learning rate schedules, applied per epoch before any warmup.
+///
+/// # Variants
+///
+/// * `Constant` - Always the trainer's `learning_rate`.
+/// * `Cyclical` - The triangular policy: the rate climbs linearly from `base` to `max` over
+///   `step_size` epochs, then back down to `base` over the next `step_size`, and repeats.
+/// * `CosineAnnealing` - Decays smoothly from `learning_rate` at epoch 0 to `eta_min` at
+///   `max_epochs` along `eta_min + 0.5 * (learning_rate - eta_min) * (1 + cos(pi * epoch / max_epochs))`,
+///   then stays at `eta_min`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum LrSchedule {
+    Constant,
+    Cyclical {
+        base: f32,
+        max: f32,
+        step_size: usize,
+    },
+    CosineAnnealing {
+        max_epochs: usize,
+        eta_min: f32,
+    },
+}
+
+impl LrSchedule {
+    /// The scheduled rate for `epoch` (0-based), given the trainer's `learning_rate`.
+    pub fn rate_at(&self, learning_rate: f32, epoch: usize) -> f32 {
+        match *self {
+            LrSchedule::Constant => learning_rate,
+            LrSchedule::Cyclical {
+                base,
+                max,
+                step_size,
+            } => {
+                let step_size = step_size.max(1);
+                let position = epoch % (2 * step_size);
+                let distance_from_peak = position.abs_diff(step_size) as f32 / step_size as f32;
+                base + (max - base) * (1.0 - distance_from_peak)
+            }
+            LrSchedule::CosineAnnealing {
+                max_epochs,
+                eta_min,
+            } => {
+                let progress = epoch.min(max_epochs) as f32 / max_epochs.max(1) as f32;
+                eta_min
+                    + 0.5
+                        * (learning_rate - eta_min)
+                        * (1.0 + (std::f32::consts::PI * progress).cos())
+            }
+        }
+    }
+}
+
+/// The validation metric watched by early stopping.
+///
+/// # Variants
+///
+/// * `Accuracy` - Stop when the validation accuracy stops increasing.
+/// * `Loss` - Stop when the validation loss (with the trainer's `loss_function`) stops decreasing.
+///   Finer-grained than the accuracy, which only changes in steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StopMetric {
+    Accuracy,
+    Loss,
+}
+
+/// How the training samples of an epoch are ordered into mini-batches.
+///
+/// # Variants
+///
+/// * `Uniform` - Every sample exactly once, in a random order.
+/// * `Balanced` - As many draws as samples, with replacement, each sample being drawn with a
+///   probability inversely proportional to the frequency of its class. Both classes then appear
+///   about equally often, oversampling the minority class instead of weighting the loss.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SamplingStrategy {
+    #[default]
+    Uniform,
+    Balanced,
+}
+
+impl SamplingStrategy {
+    /// The sample indices of one epoch, given the class (`true` = precipitation) of each sample.
+    ///
+    /// `Balanced` falls back to `Uniform` when one of the classes is absent.
+    pub fn epoch_indices<R: Rng>(&self, labels: &[bool], rng: &mut R) -> Vec<usize> {
+        let (positives, negatives): (Vec<usize>, Vec<usize>) =
+            (0..labels.len()).partition(|&i| labels[i]);
+
+        if *self == SamplingStrategy::Uniform || positives.is_empty() || negatives.is_empty() {
+            let mut indices: Vec<usize> = (0..labels.len()).collect();
+            indices.shuffle(rng);
+            return indices;
+        }
+
+        // Weighting each sample by 1 / (size of its class) gives each class half of the mass.
+        (0..labels.len())
+            .map(|_| {
+                let class = if rng.random_bool(0.5) {
+                    &positives
+                } else {
+                    &negatives
+                };
+                class[rng.random_range(0..class.len())]
+            })
+            .collect()
+    }
+}
+
+impl Default for BinaryTrainer {
+    fn default() -> Self {
+        BinaryTrainer::new(0.05, 1000, 20)
+    }
 }
 
 impl BinaryTrainer {
@@ -28,6 +192,72 @@ impl BinaryTrainer {
             learning_rate,
             epochs,
             batch_size,
+            warmup_epochs: 0,
+            label_smoothing: 0.0,
+            checkpoint_path: None,
+            normalization_params: None,
+            seed: None,
+            loss_function: LossFunction::MeanSquaredError,
+            monitor: StopMetric::Accuracy,
+            min_delta: 0.0,
+            min_epochs: 0,
+            lr_schedule: LrSchedule::Constant,
+            threshold_bound: InclusiveBound::AtLeast,
+            l1_activation_lambda: 0.0,
+            l1_activation_layer: 0,
+            sampling: SamplingStrategy::Uniform,
+            weight_clip: None,
+        }
+    }
+
+    /// Checks the hyperparameters training cannot run correctly with.
+    ///
+    /// Returns an error for a `batch_size` of 0, a `weight_clip` that is not finite and strictly
+    /// positive, or a `checkpoint_path` without the `normalization_params` the checkpoints must
+    /// be saved with.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.batch_size == 0 {
+            return Err("La taille de lot doit être strictement positive".to_string());
+        }
+        if let Some(limit) = self.weight_clip
+            && !(limit.is_finite() && limit > 0.0)
+        {
+            return Err(format!(
+                "La limite d'écrêtage des poids doit être finie et strictement positive, reçu {}",
+                limit
+            ));
+        }
+        if self.checkpoint_path.is_some() && self.normalization_params.is_none() {
+            return Err(
+                "Les paramètres de normalisation sont requis pour sauvegarder des points de contrôle"
+                    .to_string(),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// The L1 activation penalty applied during training, `None` when `l1_activation_lambda` is 0.
+    pub fn activation_penalty(&self) -> Option<L1ActivationPenalty> {
+        (self.l1_activation_lambda != 0.0).then_some(L1ActivationPenalty {
+            layer_id: self.l1_activation_layer,
+            lambda: self.l1_activation_lambda,
+        })
+    }
+
+    /// The target the network is trained towards for a hard `target` of 0.0 or 1.0.
+    pub fn smoothed_target(&self, target: f32) -> f32 {
+        target * (1.0 - 2.0 * self.label_smoothing) + self.label_smoothing
+    }
+
+    /// Effective learning rate for `epoch` (0-based): the `lr_schedule` rate, scaled by
+    /// `epoch / warmup_epochs` during warmup.
+    pub fn learning_rate_at(&self, epoch: usize) -> f32 {
+        let scheduled_rate = self.lr_schedule.rate_at(self.learning_rate, epoch);
+        if epoch < self.warmup_epochs {
+            scheduled_rate * epoch as f32 / self.warmup_epochs as f32
+        } else {
+            scheduled_rate
         }
     }
 
@@ -36,7 +266,36 @@ impl BinaryTrainer {
         input_size: usize,
         hidden_sizes: &[usize],
     ) -> NeuralNetwork {
-        let mut rng = rand::rng();
+        self.create_weather_network_with_config(input_size, hidden_sizes, &InitConfig::default())
+    }
+
+    pub fn create_weather_network_with_config(
+        &self,
+        input_size: usize,
+        hidden_sizes: &[usize],
+        init_config: &InitConfig,
+    ) -> NeuralNetwork {
+        self.create_weather_network_with_outputs(input_size, hidden_sizes, 1, init_config)
+    }
+
+    /// Builds a `input_size -> hidden_sizes -> output_size` network, e.g. for multi-output
+    /// regression. `create_weather_network_with_config` is the `output_size = 1` case.
+    ///
+    /// # Panics
+    ///
+    /// If `init_config` does not pass `InitConfig::validate`, e.g. with an inverted `bias_range`.
+    pub fn create_weather_network_with_outputs(
+        &self,
+        input_size: usize,
+        hidden_sizes: &[usize],
+        output_size: usize,
+        init_config: &InitConfig,
+    ) -> NeuralNetwork {
+        if let Err(error) = init_config.validate() {
+            panic!("Configuration d'initialisation invalide : {}", error);
+        }
+
+        let mut rng = init_config.rng();
         let mut layers = Vec::new();
         let mut prev_layer_size = input_size;
 
@@ -45,17 +304,16 @@ impl BinaryTrainer {
 
             for i in 0..layer_size {
                 let mut weights = Vec::new();
-                let weight_scale = (6.0 / (prev_layer_size + layer_size) as f32).sqrt();
 
                 for _ in 0..prev_layer_size {
-                    weights.push(rng.random_range(-weight_scale..weight_scale));
+                    weights.push(init_config.sample_weight(prev_layer_size, layer_size, &mut rng));
                 }
 
                 let neuron = Neuron::new(
                     i as u32,
                     format!("Caché{}_{}", layer_idx + 1, i),
-                    "relu".to_string(),
-                    rng.random_range(-0.1..0.1),
+                    ActivationFunction::Relu,
+                    init_config.sample_bias(&mut rng),
                     weights,
                 );
 
@@ -70,105 +328,334 @@ impl BinaryTrainer {
             prev_layer_size = layer_size;
         }
 
-        let mut output_weights = Vec::new();
-        let weight_scale = (6.0 / (prev_layer_size + 1) as f32).sqrt();
+        let mut output_neurons = Vec::new();
 
-        for _ in 0..prev_layer_size {
-            output_weights.push(rng.random_range(-weight_scale..weight_scale));
-        }
+        for i in 0..output_size {
+            let mut output_weights = Vec::new();
 
-        let output_neuron = Neuron::new(
-            0,
-            "Sortie".to_string(),
-            "sigmoid".to_string(),
-            rng.random_range(-0.1..0.1),
-            output_weights,
-        );
+            for _ in 0..prev_layer_size {
+                output_weights.push(init_config.sample_weight(
+                    prev_layer_size,
+                    output_size,
+                    &mut rng,
+                ));
+            }
+
+            // A single output keeps the historical "Sortie" name.
+            let name = if output_size == 1 {
+                "Sortie".to_string()
+            } else {
+                format!("Sortie_{}", i)
+            };
+
+            output_neurons.push(Neuron::new(
+                i as u32,
+                name,
+                ActivationFunction::Sigmoid,
+                init_config.sample_bias(&mut rng),
+                output_weights,
+            ));
+        }
 
         layers.push(Layer::new(
             hidden_sizes.len() as u32,
             "Sortie".to_string(),
-            vec![output_neuron],
+            output_neurons,
         ));
 
         NeuralNetwork::new(layers)
     }
 
+    /// Sets the output neuron's bias to the log-odds `ln(p / (1 - p))` of the precipitation
+    /// fraction `p` in `data`, so training starts from the class prior. Returns the bias.
+    ///
+    /// `p` is clamped to `[0.001, 0.999]` so a single-class dataset gives a finite bias.
+    pub fn init_output_bias_from_prior(
+        &self,
+        network: &mut NeuralNetwork,
+        data: &[SimplifiedWeatherDataPoint],
+    ) -> f32 {
+        let positives = data.iter().filter(|data_point| data_point.output).count();
+        let prior = (positives as f32 / data.len().max(1) as f32).clamp(0.001, 0.999);
+        let bias = (prior / (1.0 - prior)).ln();
+
+        if let Some(output_neuron) = network
+            .layers
+            .last_mut()
+            .and_then(|layer| layer.neurons.first_mut())
+        {
+            output_neuron.bias = bias;
+        }
+
+        bias
+    }
+
+    /// Trains `network` and returns its best validation accuracy.
+    ///
+    /// # Panics
+    ///
+    /// If the trainer does not pass `validate`, e.g. with a `batch_size` of 0 or a
+    /// `checkpoint_path` without `normalization_params`.
     pub fn train(
         &self,
         network: &mut NeuralNetwork,
         training_data: &[SimplifiedWeatherDataPoint],
         validation_data: &[SimplifiedWeatherDataPoint],
     ) -> f32 {
+        self.run_training(network, training_data, validation_data, None, None)
+    }
+
+    /// Loads the model saved at `model_path` and trains it for another `epochs` epochs.
+    ///
+    /// `training_data` and `validation_data` must already be normalized with the saved
+    /// normalization parameters. Returns the trained model and its best validation accuracy;
+    /// only its network is retrained, the saved temperature and dropout rate are kept.
+    pub fn resume<P: AsRef<Path>>(
+        &self,
+        model_path: P,
+        training_data: &[SimplifiedWeatherDataPoint],
+        validation_data: &[SimplifiedWeatherDataPoint],
+    ) -> Result<(WeatherModel, f32), Box<dyn Error>> {
+        let mut model = WeatherModel::from(pickle::load_saved_model(model_path)?);
+        let accuracy = self.train(&mut model.network, training_data, validation_data);
+
+        Ok((model, accuracy))
+    }
+
+    /// Trains like `train` and also returns the statistics of every epoch that ran.
+    pub fn train_with_history(
+        &self,
+        network: &mut NeuralNetwork,
+        training_data: &[SimplifiedWeatherDataPoint],
+        validation_data: &[SimplifiedWeatherDataPoint],
+    ) -> (f32, Vec<EpochStats>) {
+        let mut history = Vec::new();
+        let accuracy = self.run_training(
+            network,
+            training_data,
+            validation_data,
+            None,
+            Some(&mut history),
+        );
+        (accuracy, history)
+    }
+
+    /// Trains with mini-batch gradients averaged over each batch and applied by `optimizer`.
+    ///
+    /// Any `Optimizer` can be passed, e.g. `SgdOptimizer` or `RmsPropOptimizer`; the
+    /// early-stopping and logging behave as in `train`.
+    pub fn train_with_optimizer(
+        &self,
+        network: &mut NeuralNetwork,
+        training_data: &[SimplifiedWeatherDataPoint],
+        validation_data: &[SimplifiedWeatherDataPoint],
+        optimizer: &mut dyn Optimizer,
+    ) -> f32 {
+        self.run_training(
+            network,
+            training_data,
+            validation_data,
+            Some(optimizer),
+            None,
+        )
+    }
+
+    fn run_training(
+        &self,
+        network: &mut NeuralNetwork,
+        training_data: &[SimplifiedWeatherDataPoint],
+        validation_data: &[SimplifiedWeatherDataPoint],
+        mut optimizer: Option<&mut dyn Optimizer>,
+        mut history: Option<&mut Vec<EpochStats>>,
+    ) -> f32 {
+        if let Err(error) = self.validate() {
+            panic!("Configuration d'entraînement invalide : {}", error);
+        }
+
         let train_inputs = prepare_inputs(training_data);
-        let train_outputs = prepare_outputs(training_data);
+        // Accuracy is still measured against the hard targets.
+        let hard_train_outputs = prepare_outputs(training_data);
+        let train_outputs: Vec<Vec<f32>> = hard_train_outputs
+            .iter()
+            .map(|target| target.iter().map(|&t| self.smoothed_target(t)).collect())
+            .collect();
 
         let valid_inputs = prepare_inputs(validation_data);
         let valid_outputs = prepare_outputs(validation_data);
 
-        let mut best_validation_accuracy = 0.0;
+        let mut best_validation_accuracy: f32 = 0.0;
+        // The monitored metric at the last improvement, oriented so that higher is better.
+        let mut best_monitored = match self.monitor {
+            StopMetric::Accuracy => 0.0,
+            StopMetric::Loss => f32::NEG_INFINITY,
+        };
         let mut patience_counter = 0;
         let patience = 20;
 
-        println!(
+        log::info!(
             "Début de l'entraînement avec un taux d'apprentissage de : {}",
             self.learning_rate
         );
-        println!(
+        log::info!(
             "Données d'entraînement : {} échantillons, Données de validation : {} échantillons",
             train_inputs.len(),
             valid_inputs.len()
         );
 
-        let precipitation_count = training_data.iter().filter(|d| d.output).count();
-        let clear_count = training_data.len() - precipitation_count;
-        println!(
+        let (precipitation_count, clear_count, _) = class_balance(training_data);
+        log::info!(
             "Distribution des classes dans les données d'entraînement : Précipitation : {}, Clair : {}",
-            precipitation_count, clear_count
+            precipitation_count,
+            clear_count
         );
+        if let Some(warning) = imbalance_warning(training_data) {
+            log::warn!("{}", warning);
+        }
+
+        let penalty = self.activation_penalty();
+
+        let mut rng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_os_rng(),
+        };
+
+        let labels: Vec<bool> = training_data
+            .iter()
+            .map(|data_point| data_point.output)
+            .collect();
 
         for epoch in 0..self.epochs {
-            let mut indices: Vec<usize> = (0..train_inputs.len()).collect();
-            indices.shuffle(&mut rand::rng());
+            let indices = self.sampling.epoch_indices(&labels, &mut rng);
 
+            let learning_rate = self.learning_rate_at(epoch);
             let mut total_loss = 0.0;
+            let mut total_gradient_norm = 0.0;
+            let output_parameters = network.output_layer_parameter_count();
+            let mut buffers = ForwardBuffers::for_network(network);
 
             for batch_start in (0..indices.len()).step_by(self.batch_size) {
                 let batch_end = (batch_start + self.batch_size).min(indices.len());
                 let batch_indices = &indices[batch_start..batch_end];
 
+                if let Some(optimizer) = optimizer.as_deref_mut() {
+                    let mut batch_gradients = vec![0.0; network.parameter_count()];
+                    for &idx in batch_indices {
+                        let (loss, gradients) = network.parameter_gradients_regularized(
+                            &train_inputs[idx],
+                            &train_outputs[idx],
+                            self.loss_function,
+                            penalty,
+                        );
+                        let output_gradients = &gradients[gradients.len() - output_parameters..];
+                        total_gradient_norm +=
+                            output_gradients.iter().map(|g| g * g).sum::<f32>().sqrt();
+                        for (total, gradient) in batch_gradients.iter_mut().zip(gradients) {
+                            *total += gradient;
+                        }
+                        total_loss += loss;
+                    }
+
+                    let batch_len = batch_indices.len() as f32;
+                    for gradient in &mut batch_gradients {
+                        *gradient /= batch_len;
+                    }
+
+                    let mut parameters = network.get_parameters();
+                    optimizer.step(&mut parameters, &batch_gradients, learning_rate);
+                    network
+                        .set_parameters(&parameters)
+                        .expect("le nombre de paramètres ne change pas pendant l'entraînement");
+                    if let Some(limit) = self.weight_clip {
+                        network.clip_parameters(limit);
+                    }
+                    continue;
+                }
+
                 for &idx in batch_indices {
                     let input = &train_inputs[idx];
                     let target = &train_outputs[idx];
 
-                    let loss = network.backward(input, target, self.learning_rate);
+                    let (loss, gradient_norm) = network.backward_regularized(
+                        input,
+                        target,
+                        learning_rate,
+                        self.loss_function,
+                        penalty,
+                        &mut buffers,
+                    );
                     total_loss += loss;
+                    total_gradient_norm += gradient_norm;
+                    if let Some(limit) = self.weight_clip {
+                        network.clip_parameters(limit);
+                    }
                 }
             }
 
             let avg_loss = total_loss / train_inputs.len() as f32;
+            let avg_gradient_norm = total_gradient_norm / train_inputs.len() as f32;
 
-            let training_accuracy = self.evaluate_binary(network, &train_inputs, &train_outputs);
+            let training_accuracy =
+                self.evaluate_binary(network, &train_inputs, &hard_train_outputs);
             let validation_accuracy = self.evaluate_binary(network, &valid_inputs, &valid_outputs);
 
-            if epoch % 10 == 0 || epoch == self.epochs - 1 {
-                println!(
-                    "Époque {}/{} : Perte = {:.4}, Précision entraînement = {:.2}%, Précision validation = {:.2}%",
-                    epoch + 1,
-                    self.epochs,
-                    avg_loss,
-                    training_accuracy * 100.0,
-                    validation_accuracy * 100.0
-                );
+            // Every epoch is logged at debug level, every tenth (and the last) at info level.
+            let level = if epoch % 10 == 0 || epoch == self.epochs - 1 {
+                log::Level::Info
+            } else {
+                log::Level::Debug
+            };
+
+            // A full pass over the validation set, skipped when nothing reads it.
+            let validation_loss = if self.monitor == StopMetric::Loss || log::log_enabled!(level) {
+                network.compute_loss(&valid_inputs, &valid_outputs, self.loss_function)
+            } else {
+                f32::NAN
+            };
+            log::log!(
+                level,
+                "Époque {}/{} : Perte = {:.4}, Perte validation = {:.4}, Norme du gradient = {:.4}, Précision entraînement = {:.2}%, Précision validation = {:.2}%",
+                epoch + 1,
+                self.epochs,
+                avg_loss,
+                validation_loss,
+                avg_gradient_norm,
+                training_accuracy * 100.0,
+                validation_accuracy * 100.0
+            );
+
+            if let Some(history) = history.as_deref_mut() {
+                history.push(EpochStats {
+                    epoch,
+                    loss: avg_loss,
+                    gradient_norm: avg_gradient_norm,
+                    training_accuracy,
+                    validation_accuracy,
+                });
             }
 
-            if validation_accuracy > best_validation_accuracy {
-                best_validation_accuracy = validation_accuracy;
+            let monitored = match self.monitor {
+                StopMetric::Accuracy => validation_accuracy,
+                StopMetric::Loss => -validation_loss,
+            };
+            best_validation_accuracy = best_validation_accuracy.max(validation_accuracy);
+
+            // Only a real improvement moves the bar, so a run of gains each below
+            // `min_delta` still resets the patience once they add up to more than it.
+            if monitored > best_monitored + self.min_delta {
+                best_monitored = monitored;
                 patience_counter = 0;
-            } else {
+
+                if let Some(checkpoint_path) = &self.checkpoint_path
+                    && let Some(normalization_params) = &self.normalization_params
+                    && let Err(error) =
+                        pickle::save_model(network, normalization_params, checkpoint_path)
+                {
+                    log::warn!("Échec de la sauvegarde du point de contrôle : {}", error);
+                }
+            } else if epoch >= self.min_epochs {
                 patience_counter += 1;
                 if patience_counter >= patience {
-                    println!(
+                    log::info!(
                         "Arrêt anticipé déclenché ! Pas d'amélioration pendant {} époques.",
                         patience
                     );
@@ -186,31 +673,139 @@ impl BinaryTrainer {
         inputs: &[Vec<f32>],
         targets: &[Vec<f32>],
     ) -> f32 {
-        let mut correct = 0;
-        let threshold = 0.5;
+        binary_accuracy_with_bound(network, inputs, targets, 0.5, self.threshold_bound)
+    }
+}
 
-        for (i, input) in inputs.iter().enumerate() {
-            let outputs = network.activate(input);
-            let prediction = outputs.last().unwrap()[0];
-            let target = targets[i][0];
+/// Majority / minority class ratio above which training warns about the imbalance.
+pub const IMBALANCE_WARNING_RATIO: f32 = 4.0;
 
-            let predicted_class = if prediction >= threshold { 1.0 } else { 0.0 };
+/// The warning printed before training when the classes of `training_data` are imbalanced
+/// beyond `IMBALANCE_WARNING_RATIO`, or `None` when they are balanced enough.
+pub fn imbalance_warning(training_data: &[SimplifiedWeatherDataPoint]) -> Option<String> {
+    let (_, _, ratio) = class_balance(training_data);
+    if ratio <= IMBALANCE_WARNING_RATIO {
+        return None;
+    }
 
-            if predicted_class == target {
-                correct += 1;
-            }
+    Some(format!(
+        "Attention : classes déséquilibrées (rapport {:.1}:1). Envisagez des poids de classe ou un rééchantillonnage.",
+        ratio
+    ))
+}
+
+/// The statistics of one training epoch, as returned by `BinaryTrainer::train_with_history`.
+///
+/// # Fields
+///
+/// * `epoch` - The zero-based epoch index.
+/// * `loss` - The average training loss per sample.
+/// * `gradient_norm` - The average per-sample L2 norm of the output-layer weight and bias gradients.
+/// * `training_accuracy` - The accuracy on the training set after the epoch.
+/// * `validation_accuracy` - The accuracy on the validation set after the epoch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EpochStats {
+    pub epoch: usize,
+    pub loss: f32,
+    pub gradient_norm: f32,
+    pub training_accuracy: f32,
+    pub validation_accuracy: f32,
+}
+
+/// The settings a model was trained with, saved next to it by `pickle::save_model_with_options`.
+///
+/// # Fields
+///
+/// * `trainer` - The trainer hyperparameters.
+/// * `input_size` - The number of input features.
+/// * `hidden_sizes` - The neuron count of each hidden layer.
+/// * `init_config` - How the network's weights and biases were initialized, `InitConfig::default()` by default.
+/// * `optimizer` - The optimizer passed to `BinaryTrainer::train_with_optimizer`, or `None` when
+///   the network was trained with `BinaryTrainer::train`. `None` by default.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrainingConfig {
+    pub trainer: BinaryTrainer,
+    pub input_size: usize,
+    pub hidden_sizes: Vec<usize>,
+    #[serde(default)]
+    pub init_config: InitConfig,
+    #[serde(default)]
+    pub optimizer: Option<OptimizerKind>,
+}
+
+impl TrainingConfig {
+    pub fn new(trainer: BinaryTrainer, input_size: usize, hidden_sizes: Vec<usize>) -> Self {
+        TrainingConfig {
+            trainer,
+            input_size,
+            hidden_sizes,
+            init_config: InitConfig::default(),
+            optimizer: None,
         }
+    }
 
-        correct as f32 / inputs.len() as f32
+    pub fn create_network(&self) -> NeuralNetwork {
+        self.trainer.create_weather_network_with_config(
+            self.input_size,
+            &self.hidden_sizes,
+            &self.init_config,
+        )
     }
 }
 
-trait VecExt<T> {
-    fn shuffle(&mut self, rng: &mut rand::rngs::ThreadRng);
+/// The seed `reproduce` uses when the config's trainer has none.
+pub const REPRODUCTION_SEED: u64 = 42;
+
+/// Rebuilds the architecture of `config` and trains it exactly as the config says, with its
+/// `init_config` and `optimizer`. The per-epoch shuffling is seeded with `config.trainer.seed`
+/// (or `REPRODUCTION_SEED`), and so is the weight initialization unless `config.init_config`
+/// has its own seed. The same config and data always give a bit-identical network, e.g. to
+/// reproduce a published model from the config saved by `pickle::save_model_with_options`.
+///
+/// The trainer's `checkpoint_path` is ignored, so reproducing a model never overwrites the
+/// original checkpoint.
+///
+/// Returns the trained network with its best validation accuracy, as `BinaryTrainer::train`.
+pub fn reproduce(
+    config: &TrainingConfig,
+    training_data: &[SimplifiedWeatherDataPoint],
+    validation_data: &[SimplifiedWeatherDataPoint],
+) -> (NeuralNetwork, f32) {
+    let seed = config.trainer.seed.unwrap_or(REPRODUCTION_SEED);
+    let init_config = InitConfig {
+        seed: Some(config.init_config.seed.unwrap_or(seed)),
+        ..config.init_config.clone()
+    };
+    let trainer = BinaryTrainer {
+        seed: Some(seed),
+        checkpoint_path: None,
+        ..config.trainer.clone()
+    };
+
+    let mut network = trainer.create_weather_network_with_config(
+        config.input_size,
+        &config.hidden_sizes,
+        &init_config,
+    );
+    let accuracy = match config.optimizer {
+        Some(kind) => trainer.train_with_optimizer(
+            &mut network,
+            training_data,
+            validation_data,
+            kind.build().as_mut(),
+        ),
+        None => trainer.train(&mut network, training_data, validation_data),
+    };
+
+    (network, accuracy)
+}
+
+pub(crate) trait VecExt<T> {
+    fn shuffle<R: Rng>(&mut self, rng: &mut R);
 }
 
 impl<T> VecExt<T> for Vec<T> {
-    fn shuffle(&mut self, rng: &mut rand::rngs::ThreadRng) {
+    fn shuffle<R: Rng>(&mut self, rng: &mut R) {
         for i in (1..self.len()).rev() {
             let j = rng.random_range(0..=i);
             self.swap(i, j);
@@ -0,0 +1,282 @@
+use crate::back_propagation::{ForwardBuffers, NetworkExt};
+use crate::dataset_loader::{SimplifiedWeatherDataPoint, WeatherInput, normalize_with_params};
+use crate::metrics::InclusiveBound;
+use crate::neural_network::NeuralNetwork;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+const MC_DROPOUT_SEED: u64 = 42;
+
+/// A trained weather network bundled with the normalization it was trained with.
+///
+/// This is the inference-side view of a model: it takes raw `WeatherInput`s in
+/// physical units and takes care of normalizing them before activating the network.
+///
+/// # Fields
+///
+/// * `network` - The trained binary classification network.
+/// * `normalization_params` - The min/max pairs returned by `normalize_inputs` on the training data.
+/// * `temperature` - The temperature the output logit is divided by before the output activation.
+///   `1.0` leaves the network untouched; `calibrate_temperature` fits it on validation data.
+/// * `dropout_rate` - The hidden-activation dropout rate of `predict_with_uncertainty`, in `[0, 1)`. 0 (no dropout) by default.
+///   Plain predictions never use dropout.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeatherModel {
+    pub network: NeuralNetwork,
+    pub normalization_params: [f32; 8],
+    pub temperature: f32,
+    pub dropout_rate: f32,
+}
+
+impl WeatherModel {
+    pub fn new(network: NeuralNetwork, normalization_params: [f32; 8]) -> Self {
+        WeatherModel {
+            network,
+            normalization_params,
+            temperature: 1.0,
+            dropout_rate: 0.0,
+        }
+    }
+
+    /// Returns an error, leaving the temperature unchanged, unless `temperature` is finite
+    /// and strictly positive.
+    pub fn set_temperature(&mut self, temperature: f32) -> Result<(), String> {
+        if !(temperature.is_finite() && temperature > 0.0) {
+            return Err(format!(
+                "La température doit être strictement positive et finie, reçu {}",
+                temperature
+            ));
+        }
+        self.temperature = temperature;
+        Ok(())
+    }
+
+    pub fn predict_proba(&self, input: &WeatherInput) -> f32 {
+        if self.temperature == 1.0 {
+            let normalized_input = normalize_with_params(input, &self.normalization_params);
+            return self.network.predict_proba(&normalized_input.to_vec());
+        }
+
+        self.scaled_output(self.logit(input), self.temperature)
+    }
+
+    /// Whether precipitation is predicted; a probability exactly at `threshold` counts as positive.
+    pub fn predict(&self, input: &WeatherInput, threshold: f32) -> bool {
+        self.predict_with_bound(input, threshold, InclusiveBound::AtLeast)
+    }
+
+    /// `predict` with an explicit `bound` for probabilities exactly at `threshold`.
+    pub fn predict_with_bound(
+        &self,
+        input: &WeatherInput,
+        threshold: f32,
+        bound: InclusiveBound,
+    ) -> bool {
+        bound.is_positive(self.predict_proba(input), threshold)
+    }
+
+    /// (mean, standard deviation) of the probability over `samples` Monte Carlo dropout passes
+    /// at `dropout_rate`, the spread measuring how uncertain the prediction is. The masks are
+    /// drawn from a fixed seed, so repeated calls agree. Each pass is temperature-scaled like
+    /// `predict_proba`.
+    ///
+    /// Returns an error unless `0 <= dropout_rate < 1`.
+    pub fn predict_with_uncertainty(
+        &self,
+        input: &WeatherInput,
+        samples: usize,
+    ) -> Result<(f32, f32), String> {
+        if !(0.0..1.0).contains(&self.dropout_rate) {
+            return Err(format!(
+                "Le taux de dropout doit être dans [0, 1), reçu {}",
+                self.dropout_rate
+            ));
+        }
+        if samples == 0 {
+            return Ok((0.0, 0.0));
+        }
+
+        let normalized_input = normalize_with_params(input, &self.normalization_params).to_vec();
+        let mut rng = StdRng::seed_from_u64(MC_DROPOUT_SEED);
+        let mut buffers = ForwardBuffers::for_network(&self.network);
+
+        let probabilities: Vec<f32> = (0..samples)
+            .map(|_| {
+                self.network.forward_with_dropout(
+                    &normalized_input,
+                    self.dropout_rate,
+                    &mut rng,
+                    &mut buffers,
+                );
+                self.scaled_output(buffers.pre_activations.last().unwrap()[0], self.temperature)
+            })
+            .collect();
+
+        // Shifted by the first sample, so identical passes give exactly (p, 0) despite rounding.
+        let shift = probabilities[0];
+        let mean_offset = probabilities.iter().map(|p| p - shift).sum::<f32>() / samples as f32;
+        let variance = probabilities
+            .iter()
+            .map(|p| (p - shift).powi(2))
+            .sum::<f32>()
+            / samples as f32
+            - mean_offset * mean_offset;
+
+        Ok((shift + mean_offset, variance.max(0.0).sqrt()))
+    }
+
+    /// The pre-activation of the output neuron for `input`, before any temperature scaling.
+    pub fn logit(&self, input: &WeatherInput) -> f32 {
+        let normalized_input = normalize_with_params(input, &self.normalization_params);
+        let mut buffers = ForwardBuffers::default();
+        self.network
+            .forward_with_buffers(&normalized_input.to_vec(), &mut buffers);
+        buffers.pre_activations.last().unwrap()[0]
+    }
+
+    fn scaled_output(&self, logit: f32, temperature: f32) -> f32 {
+        let output_neuron = &self.network.layers.last().unwrap().neurons[0];
+        output_neuron.apply_activation_function(logit / temperature)
+    }
+}
+
+/// A `WeatherModel` behind an `Arc`, cheap to clone and share between serving threads.
+///
+/// Inference only takes `&self`, so concurrent `predict` calls on clones of the same
+/// `SharedModel` are safe and see the same weights.
+#[derive(Debug, Clone)]
+pub struct SharedModel(pub Arc<WeatherModel>);
+
+impl SharedModel {
+    pub fn new(model: WeatherModel) -> Self {
+        SharedModel(Arc::new(model))
+    }
+
+    pub fn model(&self) -> &WeatherModel {
+        &self.0
+    }
+
+    pub fn predict_proba(&self, input: &WeatherInput) -> f32 {
+        self.0.predict_proba(input)
+    }
+
+    pub fn predict(&self, input: &WeatherInput, threshold: f32) -> bool {
+        self.0.predict(input, threshold)
+    }
+
+    pub fn predict_with_bound(
+        &self,
+        input: &WeatherInput,
+        threshold: f32,
+        bound: InclusiveBound,
+    ) -> bool {
+        self.0.predict_with_bound(input, threshold, bound)
+    }
+}
+
+impl From<WeatherModel> for SharedModel {
+    fn from(model: WeatherModel) -> Self {
+        SharedModel::new(model)
+    }
+}
+
+// Fails to compile if a field ever makes the inference types unshareable across threads.
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<NeuralNetwork>();
+    assert_send_sync::<WeatherModel>();
+    assert_send_sync::<SharedModel>();
+};
+
+/// The scored prediction for one raw input, as written by `predict_dataset`.
+///
+/// # Fields
+///
+/// * `input` - The raw input, in physical units.
+/// * `probability` - The predicted probability of precipitation.
+/// * `label` - Whether precipitation is predicted, i.e. `probability >= 0.5` (`InclusiveBound::AtLeast`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Prediction {
+    pub input: WeatherInput,
+    pub probability: f32,
+    pub label: bool,
+}
+
+/// Normalizes each raw input with `normalization_params`, activates `network` and
+/// thresholds the probability at 0.5. The result serializes to a JSON array with `serde_json`.
+pub fn predict_dataset(
+    network: &NeuralNetwork,
+    normalization_params: &[f32; 8],
+    inputs: &[WeatherInput],
+) -> Vec<Prediction> {
+    inputs
+        .iter()
+        .map(|input| {
+            let normalized_input = normalize_with_params(input, normalization_params);
+            let probability = network.predict_proba(&normalized_input.to_vec());
+            Prediction {
+                input: input.clone(),
+                probability,
+                label: InclusiveBound::AtLeast.is_positive(probability, 0.5),
+            }
+        })
+        .collect()
+}
+
+/// Fits the model's temperature on validation data and returns it.
+///
+/// The temperature minimizing the negative log-likelihood of `validation_data` is found
+/// with a golden-section search over `[0.05, 20]` (on a log scale), then stored in the model.
+/// An empty `validation_data` is an error and leaves the model untouched.
+pub fn calibrate_temperature(
+    model: &mut WeatherModel,
+    validation_data: &[SimplifiedWeatherDataPoint],
+) -> Result<f32, String> {
+    if validation_data.is_empty() {
+        return Err("Impossible de calibrer la température sans données de validation".to_string());
+    }
+
+    let logits: Vec<(f32, bool)> = validation_data
+        .iter()
+        .map(|data_point| (model.logit(&data_point.input), data_point.output))
+        .collect();
+
+    let negative_log_likelihood = |log_temperature: f32| {
+        let temperature = log_temperature.exp();
+        let total: f32 = logits
+            .iter()
+            .map(|&(logit, target)| {
+                let probability = model
+                    .scaled_output(logit, temperature)
+                    .clamp(1e-7, 1.0 - 1e-7);
+                if target {
+                    -probability.ln()
+                } else {
+                    -(1.0 - probability).ln()
+                }
+            })
+            .sum();
+        total / logits.len() as f32
+    };
+
+    let golden_ratio = (5.0_f32.sqrt() - 1.0) / 2.0;
+    let mut low = 0.05_f32.ln();
+    let mut high = 20.0_f32.ln();
+
+    for _ in 0..60 {
+        let left = high - golden_ratio * (high - low);
+        let right = low + golden_ratio * (high - low);
+
+        if negative_log_likelihood(left) < negative_log_likelihood(right) {
+            high = right;
+        } else {
+            low = left;
+        }
+    }
+
+    let temperature = ((low + high) / 2.0).exp();
+    model.set_temperature(temperature)?;
+    Ok(temperature)
+}
@@ -1,26 +1,51 @@
 #[cfg(test)]
 mod tests {
 
-    use forecast_nn::dataset_loader::{self, SimplifiedWeatherDataPoint, WeatherInput};
+    use forecast_nn::activation::ActivationFunction;
+    use forecast_nn::back_propagation::{ForwardBuffers, Gradients, LayerExt, NetworkExt};
+    use forecast_nn::baseline;
+    use forecast_nn::bench;
+    use forecast_nn::cli;
+    use forecast_nn::dataset_loader::{
+        self, SimplifiedWeatherDataPoint, WeatherDataPoint, WeatherInput, WeatherOutput,
+    };
+    use forecast_nn::ensemble::Ensemble;
+    use forecast_nn::export;
+    use forecast_nn::initializer::{InitConfig, InitScheme, WeightDistribution};
     use forecast_nn::layer::Layer;
+    use forecast_nn::loss::LossFunction;
+    use forecast_nn::metrics::{self, InclusiveBound};
     use forecast_nn::neural_network::NeuralNetwork;
     use forecast_nn::neuron::Neuron;
-    use forecast_nn::pickle;
+    use forecast_nn::optimizer::{
+        AdamWOptimizer, MomentumOptimizer, Optimizer, OptimizerKind, RmsPropOptimizer,
+    };
+    use forecast_nn::pickle::{self, SaveOptions};
+    use forecast_nn::quantization;
+    use forecast_nn::trainer::{
+        self, BinaryTrainer, LrSchedule, SamplingStrategy, StopMetric, TrainingConfig,
+    };
+    use forecast_nn::weather_model::{self, SharedModel, WeatherModel};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use std::collections::HashMap;
+    use std::io::Cursor;
     use std::path::Path;
+    use std::time::Duration;
 
     #[test]
     fn test_pickle() {
         let neuron1 = Neuron::new(
             1,
             "Test1".to_string(),
-            "relu".to_string(),
+            ActivationFunction::Relu,
             0.5,
             vec![0.1, 0.2],
         );
         let neuron2 = Neuron::new(
             2,
             "Test2".to_string(),
-            "sigmoid".to_string(),
+            ActivationFunction::Sigmoid,
             0.3,
             vec![0.4, 0.5],
         );
@@ -171,4 +196,3561 @@ mod tests {
             "Le deuxième échantillon devrait être de classe négative"
         );
     }
+
+    #[test]
+    fn test_reinitialize() {
+        let trainer = BinaryTrainer::new(0.05, 10, 20);
+        let mut first = trainer.create_weather_network(4, &[8, 4]);
+        let mut second = first.clone();
+
+        first.reinitialize(InitScheme::HeNormal, 1);
+        second.reinitialize(InitScheme::HeNormal, 2);
+
+        assert_eq!(first.get_layer_ids(), second.get_layer_ids());
+        assert_eq!(first.get_layer_names(), second.get_layer_names());
+        for (layer_a, layer_b) in first.layers.iter().zip(&second.layers) {
+            assert_eq!(layer_a.get_neuron_ids(), layer_b.get_neuron_ids());
+            assert_eq!(layer_a.get_neuron_names(), layer_b.get_neuron_names());
+            assert_eq!(
+                layer_a.get_neuron_activation_functions(),
+                layer_b.get_neuron_activation_functions()
+            );
+            for (weights_a, weights_b) in layer_a
+                .get_neuron_weights()
+                .iter()
+                .zip(&layer_b.get_neuron_weights())
+            {
+                assert_eq!(weights_a.len(), weights_b.len());
+            }
+        }
+
+        assert_ne!(
+            first, second,
+            "Des graines différentes devraient produire des poids différents"
+        );
+
+        let mut replay = second.clone();
+        replay.reinitialize(InitScheme::HeNormal, 1);
+        assert_eq!(
+            first, replay,
+            "La même graine devrait produire les mêmes poids"
+        );
+    }
+
+    #[test]
+    fn test_load_dataset_from_reader() {
+        let json = r#"[
+            {
+                "input": { "temp": 28.5, "pressure": 1013.2, "altitude": 152, "humidity": 65 },
+                "output": { "forecast": "Averses l'après-midi" }
+            },
+            {
+                "input": { "temp": 15.3, "pressure": 1018.6, "altitude": 423, "humidity": 45 },
+                "output": { "forecast": "Ciel dégagé" }
+            }
+        ]"#;
+
+        let dataset = dataset_loader::load_dataset_from_reader(Cursor::new(json.as_bytes()))
+            .expect("Échec du chargement depuis un lecteur en mémoire");
+
+        assert_eq!(dataset.len(), 2, "Attendu 2 exemples");
+        assert_eq!(dataset[0].input.temp, 28.5);
+        assert_eq!(dataset[1].output.forecast, "Ciel dégagé");
+    }
+
+    #[test]
+    fn test_load_dataset_streaming() {
+        let ndjson = concat!(
+            r#"{"input": {"temp": 28.5, "pressure": 1013.2, "altitude": 152, "humidity": 65}, "output": {"forecast": "Averses"}}"#,
+            "\n",
+            r#"{"input": {"temp": 15.3, "pressure": 1018.6, "altitude": 423, "humidity": 45}, "output": {"forecast": "Ensoleillé"}}"#,
+            "\n",
+            r#"{"input": {"temp": 9.0, "pressure": 1002.4, "altitude": 820, "humidity": 91}, "output": {"forecast": "Neige"}}"#,
+            "\n",
+        );
+        let ndjson_path = std::env::temp_dir().join("forecast_nn_streaming_test.ndjson");
+        std::fs::write(&ndjson_path, ndjson).unwrap();
+
+        let points: Vec<_> = dataset_loader::load_dataset_streaming(&ndjson_path)
+            .expect("Échec de l'ouverture du fichier NDJSON")
+            .collect::<Result<_, _>>()
+            .expect("Échec de la lecture en flux");
+        assert_eq!(points.len(), 3, "Attendu 3 exemples NDJSON");
+        assert_eq!(points[2].output.forecast, "Neige");
+
+        let array_count = dataset_loader::load_dataset_streaming("weather-test-dataset.json")
+            .expect("Échec de l'ouverture du jeu de test")
+            .filter(|point| point.is_ok())
+            .count();
+        let expected = dataset_loader::load_dataset("weather-test-dataset.json")
+            .unwrap()
+            .len();
+        assert_eq!(
+            array_count, expected,
+            "Le flux d'un tableau JSON devrait tout lire"
+        );
+
+        std::fs::remove_file(&ndjson_path).unwrap_or(());
+    }
+
+    #[test]
+    fn test_load_dataset_streaming_rejects_malformed_arrays() {
+        let point = r#"{"input": {"temp": 28.5, "pressure": 1013.2, "altitude": 152, "humidity": 65}, "output": {"forecast": "Averses"}}"#;
+        let path = std::env::temp_dir().join("forecast_nn_malformed_stream_test.json");
+        let stream = |contents: &str| {
+            std::fs::write(&path, contents).unwrap();
+            dataset_loader::load_dataset_streaming(&path)
+                .expect("Échec de l'ouverture du fichier")
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        let valid = stream(&format!("[{}, {}]\n", point, point)).expect("tableau valide");
+        assert_eq!(valid.len(), 2);
+        assert!(stream("[ ]").expect("tableau vide").is_empty());
+
+        let malformed = [
+            format!("[{}, {},]", point, point),
+            format!("[{}, {}", point, point),
+            format!("[{},", point),
+            format!("[{} {}]", point, point),
+            format!("[, {}]", point),
+            format!("[{},, {}]", point, point),
+            format!("[{}] {}", point, point),
+        ];
+        for contents in &malformed {
+            assert!(
+                stream(contents).is_err(),
+                "flux accepté à tort : {}",
+                contents
+            );
+        }
+
+        std::fs::remove_file(&path).unwrap_or(());
+    }
+
+    #[test]
+    fn test_load_dataset_jsonl() {
+        let jsonl_path = std::env::temp_dir().join("forecast_nn_jsonl_test.jsonl");
+        std::fs::write(
+            &jsonl_path,
+            concat!(
+                r#"{"input": {"temp": 28.5, "pressure": 1013.2, "altitude": 152, "humidity": 65}, "output": {"forecast": "Averses"}}"#,
+                "\n\n",
+                r#"{"input": {"temp": 15.3, "pressure": 1018.6, "altitude": 423, "humidity": 45}, "output": {"forecast": "Ensoleillé"}}"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+
+        let dataset =
+            dataset_loader::load_dataset_jsonl(&jsonl_path).expect("Échec du chargement JSONL");
+        assert_eq!(dataset.len(), 2, "Les lignes vides devraient être ignorées");
+
+        std::fs::write(&jsonl_path, "\n{\"input\": {}}\n").unwrap();
+        let error = dataset_loader::load_dataset_jsonl(&jsonl_path).unwrap_err();
+        assert!(
+            error.to_string().contains("Ligne 2"),
+            "L'erreur devrait indiquer le numéro de ligne : {}",
+            error
+        );
+
+        std::fs::remove_file(&jsonl_path).unwrap_or(());
+    }
+
+    fn forecast_point(forecast: &str) -> WeatherDataPoint {
+        WeatherDataPoint {
+            input: WeatherInput {
+                temp: 20.0,
+                pressure: 1015.0,
+                altitude: 300.0,
+                humidity: 50.0,
+            },
+            output: WeatherOutput {
+                forecast: forecast.to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_simplify_forecasts_keyword_matching() {
+        let dataset = vec![
+            forecast_point("Humidité faible, ciel dégagé"),
+            forecast_point("FORTES PRÉCIPITATIONS attendues"),
+            forecast_point("Risque de precipitation en soirée"),
+            forecast_point("Possibilité d'averses"),
+        ];
+
+        let labels: Vec<bool> = dataset_loader::simplify_forecasts(&dataset)
+            .iter()
+            .map(|point| point.output)
+            .collect();
+
+        assert_eq!(
+            labels,
+            vec![false, true, true, true],
+            "Classification inattendue des prévisions"
+        );
+    }
+
+    #[test]
+    fn test_simplify_forecasts_matches_derived_forms() {
+        let forecasts = [
+            "Temps pluvieux",
+            "Journée pluvieuse",
+            "Ciel orageux",
+            "Soirées orageuses",
+            "Temps neigeux",
+            "Matinée neigeuse",
+            "Ciel bruineux",
+            "Atmosphère lourde",
+            "Rafale de vent",
+        ];
+        let dataset: Vec<WeatherDataPoint> = forecasts.iter().map(|f| forecast_point(f)).collect();
+
+        for (forecast, point) in forecasts
+            .iter()
+            .zip(dataset_loader::simplify_forecasts(&dataset))
+        {
+            assert!(point.output, "« {} » doit être une précipitation", forecast);
+        }
+    }
+
+    #[test]
+    fn test_simplify_forecasts_with_keywords() {
+        let dataset = vec![
+            forecast_point("Heavy rain expected in the afternoon"),
+            forecast_point("Clear skies and light winds"),
+            forecast_point("Snow showers overnight"),
+        ];
+
+        let labels: Vec<bool> =
+            dataset_loader::simplify_forecasts_with_keywords(&dataset, &["rain", "snow"])
+                .iter()
+                .map(|point| point.output)
+                .collect();
+
+        assert_eq!(labels, vec![true, false, true]);
+    }
+
+    fn temperature_threshold_network() -> NeuralNetwork {
+        let neuron = Neuron::new(
+            0,
+            "Sortie".to_string(),
+            ActivationFunction::Sigmoid,
+            -5.0,
+            vec![10.0, 0.0, 0.0, 0.0],
+        );
+        NeuralNetwork::new(vec![Layer::new(0, "Sortie".to_string(), vec![neuron])])
+    }
+
+    #[test]
+    fn test_permutation_importance() {
+        let network = temperature_threshold_network();
+        let inputs: Vec<Vec<f32>> = (0..40)
+            .map(|i| {
+                let temp = (i as f32 + 0.5) / 40.0;
+                vec![
+                    temp,
+                    (i % 7) as f32 / 7.0,
+                    (i % 3) as f32 / 3.0,
+                    (i % 5) as f32 / 5.0,
+                ]
+            })
+            .collect();
+        let targets: Vec<Vec<f32>> = inputs
+            .iter()
+            .map(|input| vec![if input[0] > 0.5 { 1.0 } else { 0.0 }])
+            .collect();
+
+        let importances = metrics::permutation_importance(&network, &inputs, &targets);
+
+        assert!(
+            importances[0] > 0.2,
+            "La température devrait être importante : {:?}",
+            importances
+        );
+        for &importance in &importances[1..] {
+            assert!(
+                importance < importances[0],
+                "La température devrait être la plus importante : {:?}",
+                importances
+            );
+        }
+    }
+
+    #[test]
+    fn test_input_gradient() {
+        let weights = vec![0.4, -1.5, 2.0, 0.25];
+        let neuron = Neuron::new(
+            0,
+            "Linéaire".to_string(),
+            ActivationFunction::Linear,
+            0.3,
+            weights.clone(),
+        );
+        let network = NeuralNetwork::new(vec![Layer::new(0, "Sortie".to_string(), vec![neuron])]);
+
+        let gradient = network.input_gradient(&[0.1, 0.2, 0.3, 0.4]);
+
+        assert_eq!(
+            gradient, weights,
+            "Le gradient d'un neurone linéaire devrait être ses poids"
+        );
+    }
+
+    #[test]
+    fn test_f64_network_matches_f32() {
+        let network = BinaryTrainer::new(0.05, 10, 20).create_weather_network(4, &[8, 4]);
+        let network_f64 = NeuralNetwork::<f64>::new(
+            network
+                .layers
+                .iter()
+                .map(|layer| {
+                    let neurons = layer
+                        .neurons
+                        .iter()
+                        .map(|neuron| {
+                            Neuron::new(
+                                neuron.id,
+                                neuron.name.clone(),
+                                neuron.activation_function,
+                                neuron.bias as f64,
+                                neuron.weights.iter().map(|&w| w as f64).collect(),
+                            )
+                        })
+                        .collect();
+                    Layer::new(layer.id, layer.name.clone(), neurons)
+                })
+                .collect(),
+        );
+
+        let prediction = network.activate(&[0.2, 0.5, 0.7, 0.9]).last().unwrap()[0];
+        let prediction_f64 = network_f64.activate(&[0.2, 0.5, 0.7, 0.9]).last().unwrap()[0];
+
+        assert!(
+            (prediction as f64 - prediction_f64).abs() < 1e-5,
+            "Les réseaux f32 et f64 devraient concorder : {} vs {}",
+            prediction,
+            prediction_f64
+        );
+    }
+
+    fn constant_model(probability: f32) -> WeatherModel {
+        let neuron = Neuron::new(
+            0,
+            "Sortie".to_string(),
+            ActivationFunction::Linear,
+            probability,
+            vec![0.0; 4],
+        );
+        let network = NeuralNetwork::new(vec![Layer::new(0, "Sortie".to_string(), vec![neuron])]);
+        WeatherModel::new(network, [0.0, 40.0, 950.0, 1050.0, 0.0, 2000.0, 0.0, 100.0])
+    }
+
+    #[test]
+    fn test_ensemble_averaging() {
+        let input = WeatherInput {
+            temp: 20.0,
+            pressure: 1015.0,
+            altitude: 300.0,
+            humidity: 60.0,
+        };
+
+        let ensemble = Ensemble::new(vec![constant_model(0.3), constant_model(0.7)]).unwrap();
+        assert!((ensemble.predict_proba(&input) - 0.5).abs() < 1e-6);
+        assert!(ensemble.predict(&input, 0.5));
+
+        let weighted = Ensemble::with_weights(
+            vec![constant_model(0.3), constant_model(0.7)],
+            vec![3.0, 1.0],
+        )
+        .unwrap();
+        assert!((weighted.predict_proba(&input) - 0.4).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_ensemble_round_trip() {
+        let trainer = BinaryTrainer::new(0.05, 10, 20);
+        let norm_params = [0.0, 40.0, 950.0, 1050.0, 0.0, 2000.0, 0.0, 100.0];
+        let models = (0..3)
+            .map(|_| WeatherModel::new(trainer.create_weather_network(4, &[8, 4]), norm_params))
+            .collect();
+        let ensemble = Ensemble::with_weights(models, vec![1.0, 2.0, 0.5]).unwrap();
+
+        let ensemble_path = std::env::temp_dir().join("forecast_nn_ensemble_test.json");
+        pickle::save_ensemble(&ensemble, &ensemble_path).expect("Échec de la sauvegarde");
+        let loaded = pickle::load_ensemble(&ensemble_path).expect("Échec du chargement");
+        std::fs::remove_file(&ensemble_path).unwrap_or(());
+
+        let input = WeatherInput {
+            temp: 25.0,
+            pressure: 1008.0,
+            altitude: 120.0,
+            humidity: 80.0,
+        };
+        assert_eq!(loaded.models.len(), 3);
+        assert_eq!(loaded.predict_proba(&input), ensemble.predict_proba(&input));
+    }
+
+    #[test]
+    fn test_prediction_entropy() {
+        let input = [0.5, 0.5, 0.5, 0.5];
+        let entropy = |probability: f32| {
+            constant_model(probability)
+                .network
+                .prediction_entropy(&input)
+        };
+
+        assert!((entropy(0.5) - 1.0).abs() < 1e-6, "Entropie maximale à 0.5");
+        assert_eq!(entropy(0.0), 0.0, "Entropie nulle à p = 0");
+        assert_eq!(entropy(1.0), 0.0, "Entropie nulle à p = 1");
+        assert!(entropy(0.9) < entropy(0.6));
+    }
+
+    fn noisy_temperature_data(offset: f32) -> Vec<SimplifiedWeatherDataPoint> {
+        (0..60)
+            .map(|i| {
+                let temp = (i as f32 + offset) / 60.0;
+                SimplifiedWeatherDataPoint {
+                    input: WeatherInput {
+                        temp,
+                        pressure: 0.5,
+                        altitude: 0.5,
+                        humidity: 0.5,
+                    },
+                    output: (temp > 0.5) != (i % 4 == 0),
+                }
+            })
+            .collect()
+    }
+
+    // Same temperatures as `noisy_temperature_data`, without the flipped labels.
+    fn clean_temperature_data() -> Vec<SimplifiedWeatherDataPoint> {
+        (0..60)
+            .map(|i| {
+                let temp = i as f32 / 60.0;
+                SimplifiedWeatherDataPoint {
+                    input: WeatherInput {
+                        temp,
+                        pressure: 0.5,
+                        altitude: 0.5,
+                        humidity: 0.5,
+                    },
+                    output: temp > 0.5,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_temperature_calibration() {
+        let neuron = Neuron::new(
+            0,
+            "Sortie".to_string(),
+            ActivationFunction::Sigmoid,
+            -20.0,
+            vec![40.0, 0.0, 0.0, 0.0],
+        );
+        let network = NeuralNetwork::new(vec![Layer::new(0, "Sortie".to_string(), vec![neuron])]);
+        let mut model = WeatherModel::new(network, [0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0]);
+
+        let held_out = noisy_temperature_data(0.25);
+        let log_likelihood = |model: &WeatherModel| {
+            held_out
+                .iter()
+                .map(|data_point| {
+                    let p = model
+                        .predict_proba(&data_point.input)
+                        .clamp(1e-7, 1.0 - 1e-7);
+                    if data_point.output {
+                        -p.ln()
+                    } else {
+                        -(1.0 - p).ln()
+                    }
+                })
+                .sum::<f32>()
+                / held_out.len() as f32
+        };
+
+        let uncalibrated = log_likelihood(&model);
+        let temperature =
+            weather_model::calibrate_temperature(&mut model, &noisy_temperature_data(0.75))
+                .unwrap();
+        let calibrated = log_likelihood(&model);
+
+        assert!(
+            temperature > 1.0,
+            "Un modèle trop confiant devrait être refroidi"
+        );
+        assert!(
+            calibrated < uncalibrated,
+            "La calibration devrait réduire la log-vraisemblance négative : {} vs {}",
+            calibrated,
+            uncalibrated
+        );
+    }
+
+    #[test]
+    fn test_create_weather_network_with_config() {
+        let trainer = BinaryTrainer::new(0.05, 10, 20);
+        let init_config = InitConfig {
+            weight_scale: Some(0.01),
+            bias_range: (0.2, 0.3),
+            seed: Some(7),
+            ..InitConfig::default()
+        };
+
+        let network = trainer.create_weather_network_with_config(4, &[8, 4], &init_config);
+        for layer in &network.layers {
+            for neuron in &layer.neurons {
+                assert!(
+                    (0.2..=0.3).contains(&neuron.bias),
+                    "Biais hors de l'intervalle : {}",
+                    neuron.bias
+                );
+                assert!(neuron.weights.iter().all(|w| w.abs() <= 0.01));
+            }
+        }
+
+        let replay = trainer.create_weather_network_with_config(4, &[8, 4], &init_config);
+        assert_eq!(
+            network, replay,
+            "La même graine devrait donner le même réseau"
+        );
+    }
+
+    #[test]
+    fn test_forward_buffers_are_reused() {
+        let mut network = BinaryTrainer::new(0.05, 10, 20).create_weather_network(4, &[8, 4]);
+        let mut buffers = ForwardBuffers::for_network(&network);
+
+        network.forward_with_buffers(&[0.1, 0.2, 0.3, 0.4], &mut buffers);
+        let pointers: Vec<*const f32> = buffers.layer_outputs.iter().map(|v| v.as_ptr()).collect();
+        assert_eq!(
+            buffers.layer_outputs,
+            network.forward_with_cache(&[0.1, 0.2, 0.3, 0.4])
+        );
+
+        for i in 0..50 {
+            let inputs = [i as f32 / 50.0, 0.5, 0.5, 0.5];
+            network.backward_with_buffers(&inputs, &[1.0], 0.01, &mut buffers);
+        }
+
+        let reused: Vec<*const f32> = buffers.layer_outputs.iter().map(|v| v.as_ptr()).collect();
+        assert_eq!(
+            pointers, reused,
+            "Les tampons ne devraient pas être réalloués entre les passes"
+        );
+    }
+
+    #[test]
+    fn test_quantization_fidelity() {
+        let model_path = Path::new("weather_model.json");
+        if !model_path.exists() {
+            println!("Fichier modèle introuvable, test de quantification ignoré");
+            return;
+        }
+
+        let saved_model = pickle::load_saved_model(model_path).unwrap();
+        let (network, norm_params) = (saved_model.network, saved_model.normalization_params);
+        let quantized = quantization::quantize(&network);
+        let test_data =
+            dataset_loader::load_dataset("weather-test-dataset.json").expect("Jeu de test absent");
+
+        let inputs: Vec<Vec<f32>> = test_data
+            .iter()
+            .map(|point| dataset_loader::normalize_with_params(&point.input, &norm_params).to_vec())
+            .collect();
+        let agreements = inputs
+            .iter()
+            .filter(|input| {
+                (network.predict_proba(input) >= 0.5) == (quantized.activate(input)[0] >= 0.5)
+            })
+            .count();
+
+        assert!(
+            agreements as f32 / inputs.len() as f32 >= 0.95,
+            "Le modèle quantifié devrait concorder sur au moins 95% des cas ({}/{})",
+            agreements,
+            inputs.len()
+        );
+    }
+
+    #[test]
+    fn test_prune() {
+        let neuron = Neuron::new(
+            0,
+            "Sortie".to_string(),
+            ActivationFunction::Sigmoid,
+            0.0001,
+            vec![0.5, -0.0005, 0.002, -0.8],
+        );
+        let mut network =
+            NeuralNetwork::new(vec![Layer::new(0, "Sortie".to_string(), vec![neuron])]);
+
+        assert_eq!(network.prune(0.01), 2, "Attendu 2 poids élagués");
+        let neuron = &network.layers[0].neurons[0];
+        assert_eq!(neuron.weights, vec![0.5, 0.0, 0.0, -0.8]);
+        assert_eq!(neuron.bias, 0.0001, "Le biais ne devrait pas être élagué");
+        assert_eq!(network.prune(0.01), 0);
+    }
+
+    #[test]
+    fn test_activation_report_dead_neurons() {
+        let alive = Neuron::new(
+            0,
+            "Vivant".to_string(),
+            ActivationFunction::Relu,
+            0.1,
+            vec![1.0, 1.0],
+        );
+        let dead = Neuron::new(
+            1,
+            "Mort".to_string(),
+            ActivationFunction::Relu,
+            -1.0,
+            vec![-1.0, -1.0],
+        );
+        let network: NeuralNetwork =
+            NeuralNetwork::new(vec![Layer::new(0, "Caché1".to_string(), vec![alive, dead])]);
+
+        let report = network.activation_report(&[vec![0.2, 0.4], vec![0.9, 0.1]]);
+
+        assert_eq!(report.len(), 1);
+        assert_eq!(
+            report[0].dead_fraction, 0.5,
+            "Un neurone sur deux devrait être mort"
+        );
+        assert!((report[0].max_activation - 1.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_grow_layer() {
+        let mut network = BinaryTrainer::new(0.05, 10, 20).create_weather_network(4, &[8, 4]);
+        let original = network.clone();
+
+        network.grow_layer(0, 3, 42);
+
+        assert_eq!(network.layers[0].get_neuron_count(), 11);
+        assert_eq!(
+            network.layers[0].neurons[..8],
+            original.layers[0].neurons[..]
+        );
+        for neuron in &network.layers[1].neurons {
+            assert_eq!(
+                neuron.weights.len(),
+                11,
+                "Les poids en aval devraient suivre la couche agrandie"
+            );
+        }
+
+        let outputs = network.activate_final(&[0.1, 0.2, 0.3, 0.4]);
+        assert_eq!(outputs.len(), 1);
+    }
+
+    #[test]
+    fn test_parameters_round_trip() {
+        let mut network = BinaryTrainer::new(0.05, 10, 20).create_weather_network(4, &[8, 4]);
+        assert_eq!(
+            network.parameter_count(),
+            (4 * 8 + 8) + (8 * 4 + 4) + (4 + 1)
+        );
+
+        let mut parameters = network.get_parameters();
+        assert_eq!(parameters.len(), network.parameter_count());
+        assert_eq!(parameters[4], network.layers[0].neurons[0].bias);
+
+        for parameter in &mut parameters {
+            *parameter += 1.0;
+        }
+        network.set_parameters(&parameters).unwrap();
+        assert_eq!(network.get_parameters(), parameters);
+
+        assert!(
+            network.set_parameters(&parameters[1..]).is_err(),
+            "Une longueur incorrecte devrait être refusée"
+        );
+    }
+
+    fn assert_gradients_match_finite_differences(network: &NeuralNetwork) {
+        let inputs = [0.3, 0.7, 0.2, 0.9];
+        let targets = [1.0];
+
+        let (_, gradients) = network.parameter_gradients(&inputs, &targets);
+        let parameters = network.get_parameters();
+        let epsilon = 1e-3;
+
+        for i in 0..parameters.len() {
+            let mut shifted = network.clone();
+            let mut plus = parameters.clone();
+            plus[i] += epsilon;
+            shifted.set_parameters(&plus).unwrap();
+            let loss_plus = shifted.parameter_gradients(&inputs, &targets).0;
+
+            let mut minus = parameters.clone();
+            minus[i] -= epsilon;
+            shifted.set_parameters(&minus).unwrap();
+            let loss_minus = shifted.parameter_gradients(&inputs, &targets).0;
+
+            let numerical = (loss_plus - loss_minus) / (2.0 * epsilon);
+            assert!(
+                (numerical - gradients[i]).abs() < 1e-3,
+                "Gradient du paramètre {} : analytique {}, numérique {}",
+                i,
+                gradients[i],
+                numerical
+            );
+        }
+    }
+
+    #[test]
+    fn test_parameter_gradients_match_finite_differences() {
+        let config = InitConfig {
+            seed: Some(3),
+            ..InitConfig::default()
+        };
+        let network = BinaryTrainer::new(0.05, 10, 20).create_weather_network_with_config(
+            4,
+            &[5, 3],
+            &config,
+        );
+        assert_gradients_match_finite_differences(&network);
+    }
+
+    #[test]
+    fn test_rmsprop_optimizer() {
+        let mut optimizer = RmsPropOptimizer::default();
+        let mut parameters = vec![1.0, -1.0];
+        optimizer.step(&mut parameters, &[2.0, 0.0], 0.01);
+        optimizer.step(&mut parameters, &[2.0, 0.0], 0.01);
+
+        // After two identical gradients: 0.9 * (0.1 * 4) + 0.1 * 4.
+        assert!((optimizer.squared_gradient_average[0] - 0.76).abs() < 1e-6);
+        assert_eq!(optimizer.squared_gradient_average[1], 0.0);
+        assert_eq!(
+            parameters[1], -1.0,
+            "Un gradient nul ne doit pas déplacer le paramètre"
+        );
+
+        let data: Vec<SimplifiedWeatherDataPoint> = (0..60)
+            .map(|i| {
+                let temp = i as f32 / 60.0;
+                SimplifiedWeatherDataPoint {
+                    input: WeatherInput {
+                        temp,
+                        pressure: 0.5,
+                        altitude: 0.5,
+                        humidity: 0.5,
+                    },
+                    output: temp > 0.5,
+                }
+            })
+            .collect();
+        let trainer = BinaryTrainer::new(0.01, 300, 10);
+        let config = InitConfig {
+            seed: Some(11),
+            ..InitConfig::default()
+        };
+        let mut network = trainer.create_weather_network_with_config(4, &[6], &config);
+
+        let accuracy = trainer.train_with_optimizer(
+            &mut network,
+            &data,
+            &data,
+            &mut RmsPropOptimizer::default(),
+        );
+        assert!(
+            accuracy > 0.9,
+            "RMSProp devrait apprendre un seuil de température, précision {}",
+            accuracy
+        );
+    }
+
+    #[test]
+    fn test_nesterov_momentum() {
+        // f(x) = x² / 2, so the gradient is x itself.
+        let trajectory = |nesterov: bool| {
+            let mut optimizer = MomentumOptimizer::new(0.9, nesterov);
+            let mut x = vec![1.0];
+            let mut positions = Vec::new();
+            for _ in 0..50 {
+                let gradient = x.clone();
+                optimizer.step(&mut x, &gradient, 0.1);
+                positions.push(x[0]);
+            }
+            positions
+        };
+
+        let plain = trajectory(false);
+        let nesterov = trajectory(true);
+
+        assert!((plain[0] - 0.9).abs() < 1e-6);
+        assert!((nesterov[0] - 0.81).abs() < 1e-6);
+        assert!(
+            nesterov[49].abs() < plain[49].abs(),
+            "Nesterov devrait converger plus vite : {} contre {}",
+            nesterov[49],
+            plain[49]
+        );
+    }
+
+    #[test]
+    fn test_learning_rate_warmup() {
+        let mut trainer = BinaryTrainer::new(0.05, 100, 20);
+        assert_eq!(trainer.learning_rate_at(0), 0.05);
+
+        trainer.warmup_epochs = 10;
+        assert!(trainer.learning_rate_at(0).abs() < 1e-6);
+        assert!((trainer.learning_rate_at(5) - 0.025).abs() < 1e-6);
+        assert_eq!(trainer.learning_rate_at(10), 0.05);
+        assert_eq!(trainer.learning_rate_at(50), 0.05);
+    }
+
+    #[test]
+    fn test_cli_predict() {
+        let model_path = std::env::temp_dir().join("forecast_nn_cli_predict_test.json");
+        let normalization_params = [0.0, 40.0, 950.0, 1050.0, 0.0, 1000.0, 0.0, 100.0];
+        pickle::save_model(
+            &temperature_threshold_network(),
+            &normalization_params,
+            &model_path,
+        )
+        .unwrap();
+
+        let args = |temp: &str| -> Vec<String> {
+            [
+                "predict",
+                "--temp",
+                temp,
+                "--pressure",
+                "1016",
+                "--altitude",
+                "300",
+                "--humidity",
+                "70",
+                "--model",
+                model_path.to_str().unwrap(),
+            ]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect()
+        };
+
+        let warm = cli::run(&args("35")).unwrap();
+        assert!(warm.contains(cli::verdict(true)), "Sortie : {}", warm);
+        let cold = cli::run(&args("5")).unwrap();
+        assert!(cold.contains(cli::verdict(false)), "Sortie : {}", cold);
+
+        assert!(cli::run(&args("chaud")).is_err());
+
+        std::fs::remove_file(&model_path).unwrap_or(());
+    }
+
+    #[test]
+    fn test_cli_predict_batch() {
+        let model_path = std::env::temp_dir().join("forecast_nn_cli_batch_model_test.json");
+        let input_path = std::env::temp_dir().join("forecast_nn_cli_batch_input_test.csv");
+        let output_path = std::env::temp_dir().join("forecast_nn_cli_batch_output_test.csv");
+        let normalization_params = [0.0, 40.0, 950.0, 1050.0, 0.0, 1000.0, 0.0, 100.0];
+        pickle::save_model(
+            &temperature_threshold_network(),
+            &normalization_params,
+            &model_path,
+        )
+        .unwrap();
+        std::fs::write(
+            &input_path,
+            "temp,pressure,altitude,humidity\n35,1016,300,70\n5,1016,300,70\n",
+        )
+        .unwrap();
+
+        let count = cli::predict_batch(&input_path, &output_path, &model_path).unwrap();
+        assert_eq!(count, 2);
+
+        let output = std::fs::read_to_string(&output_path).unwrap();
+        let rows: Vec<&str> = output.lines().skip(1).collect();
+        assert_eq!(rows.len(), 2);
+        assert!(
+            rows[0].starts_with("35,") && rows[0].ends_with(",1"),
+            "Ligne : {}",
+            rows[0]
+        );
+        assert!(
+            rows[1].starts_with("5,") && rows[1].ends_with(",0"),
+            "Ligne : {}",
+            rows[1]
+        );
+
+        for path in [&model_path, &input_path, &output_path] {
+            std::fs::remove_file(path).unwrap_or(());
+        }
+    }
+
+    #[test]
+    fn test_cli_predict_and_predict_batch_apply_temperature() {
+        let model_path = std::env::temp_dir().join("forecast_nn_cli_temperature_model_test.json");
+        let input_path = std::env::temp_dir().join("forecast_nn_cli_temperature_input_test.csv");
+        let output_path = std::env::temp_dir().join("forecast_nn_cli_temperature_output_test.csv");
+        let options = SaveOptions {
+            temperature: 2.5,
+            dropout_rate: 0.2,
+            ..SaveOptions::default()
+        };
+        pickle::save_model_with_options(
+            &temperature_threshold_network(),
+            &[0.0, 40.0, 950.0, 1050.0, 0.0, 1000.0, 0.0, 100.0],
+            &options,
+            &model_path,
+        )
+        .unwrap();
+        std::fs::write(&input_path, "28,1016,300,70\n").unwrap();
+
+        let input = WeatherInput {
+            temp: 28.0,
+            pressure: 1016.0,
+            altitude: 300.0,
+            humidity: 70.0,
+        };
+        let single = cli::predict(&input, &model_path).unwrap();
+        cli::predict_batch(&input_path, &output_path, &model_path).unwrap();
+        let batch = std::fs::read_to_string(&output_path).unwrap();
+        let batch_probability = batch.lines().nth(1).unwrap().split(',').nth(4).unwrap();
+        assert!(
+            single.contains(&format!(": {}\n", batch_probability)),
+            "predict : {}, predict-batch : {}",
+            single,
+            batch_probability
+        );
+
+        let untempered = WeatherModel::new(
+            temperature_threshold_network(),
+            [0.0, 40.0, 950.0, 1050.0, 0.0, 1000.0, 0.0, 100.0],
+        );
+        assert_ne!(
+            batch_probability,
+            format!("{:.4}", untempered.predict_proba(&input)),
+            "la température doit changer la probabilité"
+        );
+
+        let data = clean_temperature_data();
+        let (resumed, _) = BinaryTrainer::new(0.05, 2, 10)
+            .resume(&model_path, &data, &data)
+            .unwrap();
+        assert_eq!(resumed.temperature, 2.5);
+        assert_eq!(resumed.dropout_rate, 0.2);
+
+        for path in [&model_path, &input_path, &output_path] {
+            std::fs::remove_file(path).unwrap_or(());
+        }
+    }
+
+    #[test]
+    fn test_cli_train() {
+        let data_path = std::env::temp_dir().join("forecast_nn_cli_train_data_test.json");
+        let out_path = std::env::temp_dir().join("forecast_nn_cli_train_model_test.json");
+        let dataset: Vec<WeatherDataPoint> = (0..8)
+            .map(|i| WeatherDataPoint {
+                input: WeatherInput {
+                    temp: i as f32 * 4.0,
+                    pressure: 1000.0 + i as f32,
+                    altitude: 100.0 * i as f32,
+                    humidity: 40.0 + 5.0 * i as f32,
+                },
+                output: WeatherOutput {
+                    forecast: if i % 2 == 0 { "Pluie" } else { "Ensoleillé" }.to_string(),
+                },
+            })
+            .collect();
+        std::fs::write(&data_path, serde_json::to_string(&dataset).unwrap()).unwrap();
+
+        let args: Vec<String> = [
+            "train",
+            "--lr",
+            "0.1",
+            "--epochs",
+            "3",
+            "--batch-size",
+            "4",
+            "--hidden",
+            "3",
+            "--train-data",
+            data_path.to_str().unwrap(),
+            "--test-data",
+            data_path.to_str().unwrap(),
+            "--out",
+            out_path.to_str().unwrap(),
+        ]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+        cli::run(&args).unwrap();
+
+        let saved_model = pickle::load_saved_model(&out_path).unwrap();
+        assert_eq!(saved_model.network.layers.len(), 2);
+        assert_eq!(saved_model.network.layers[0].neurons.len(), 3);
+        let training_config = saved_model
+            .training_config
+            .expect("La configuration devrait être sauvegardée");
+        assert_eq!(training_config.trainer.epochs, 3);
+        assert_eq!(training_config.hidden_sizes, vec![3]);
+
+        // `--pre-normalized` takes no value, so the next option still parses.
+        let mut args = args;
+        args.insert(1, "--pre-normalized".to_string());
+        cli::run(&args).unwrap();
+        let saved_model = pickle::load_saved_model(&out_path).unwrap();
+        assert_eq!(
+            saved_model.normalization_params,
+            dataset_loader::IDENTITY_NORMALIZATION
+        );
+
+        std::fs::remove_file(&data_path).unwrap_or(());
+        std::fs::remove_file(&out_path).unwrap_or(());
+    }
+
+    #[test]
+    fn test_activation_function_serde() {
+        let json =
+            r#"{"id":0,"name":"N","activation_function":"sigmiod","bias":0.0,"weights":[1.0]}"#;
+        let result: Result<Neuron, _> = serde_json::from_str(json);
+        let error = result.expect_err("Une activation inconnue devrait être refusée");
+        assert!(error.to_string().contains("sigmiod"), "Erreur : {}", error);
+
+        let neuron = Neuron::new(0, "N".to_string(), ActivationFunction::Tanh, 0.0, vec![1.0]);
+        let json = serde_json::to_string(&neuron).unwrap();
+        assert!(json.contains(r#""activation_function":"tanh""#));
+        assert_eq!(serde_json::from_str::<Neuron>(&json).unwrap(), neuron);
+
+        assert!((neuron.activate(&[0.5]) - 0.5f32.tanh()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_neuron_try_new_rejects_unknown_activation() {
+        let result = Neuron::try_new(0, "N".to_string(), "reul", 0.0, vec![1.0f32]);
+        assert!(
+            result.is_err(),
+            "\"reul\" ne devrait pas devenir une activation linéaire"
+        );
+
+        let neuron = Neuron::try_new(0, "N".to_string(), "relu", 0.0, vec![1.0f32]).unwrap();
+        assert_eq!(neuron.activation_function, ActivationFunction::Relu);
+        assert_eq!(neuron.activate(&[-2.0]), 0.0);
+    }
+
+    #[test]
+    fn test_swish_activation() {
+        let swish = ActivationFunction::Swish;
+        assert_eq!(swish.apply(0.0f32), 0.0);
+        assert!((swish.apply(2.0f32) - 2.0 / (1.0 + (-2.0f32).exp())).abs() < 1e-6);
+        assert!(swish.apply(-1.0f32) < 0.0, "Swish n'est pas nul pour x < 0");
+
+        let config = InitConfig {
+            seed: Some(5),
+            ..InitConfig::default()
+        };
+        let mut network = BinaryTrainer::new(0.05, 10, 20).create_weather_network_with_config(
+            4,
+            &[5, 3],
+            &config,
+        );
+        for layer in &mut network.layers[..2] {
+            for neuron in &mut layer.neurons {
+                neuron.activation_function = ActivationFunction::Swish;
+            }
+        }
+        assert_gradients_match_finite_differences(&network);
+    }
+
+    #[test]
+    fn test_softplus_activation() {
+        let softplus = ActivationFunction::Softplus;
+        assert!((softplus.apply(0.0f32) - 2.0f32.ln()).abs() < 1e-6);
+
+        let large = softplus.apply(100.0f32);
+        assert!(large.is_finite(), "softplus(100) ne doit pas déborder");
+        assert!((large - 100.0).abs() < 1e-4);
+        assert!(softplus.apply(-100.0f32) >= 0.0);
+
+        assert!((softplus.derivative(0.0f32, softplus.apply(0.0f32)) - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_compute_loss() {
+        let config = InitConfig {
+            seed: Some(8),
+            ..InitConfig::default()
+        };
+        let mut network =
+            BinaryTrainer::new(0.05, 10, 20).create_weather_network_with_config(4, &[4], &config);
+        let inputs = vec![vec![0.2, 0.4, 0.6, 0.8]];
+        let targets = vec![vec![1.0]];
+
+        let before = network.clone();
+        let mse = network.compute_loss(&inputs, &targets, LossFunction::MeanSquaredError);
+        assert_eq!(
+            network, before,
+            "compute_loss ne doit pas modifier les poids"
+        );
+
+        let pre_update_loss = network.backward(&inputs[0], &targets[0], 0.05);
+        assert!((mse - pre_update_loss).abs() < 1e-6);
+
+        let output = before.predict_proba(&inputs[0]);
+        let bce = before.compute_loss(&inputs, &targets, LossFunction::BinaryCrossEntropy);
+        assert!((bce + output.ln()).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_confusion_matrix_display() {
+        let network = temperature_threshold_network();
+        let inputs = vec![
+            vec![0.9, 0.0, 0.0, 0.0],
+            vec![0.8, 0.0, 0.0, 0.0],
+            vec![0.7, 0.0, 0.0, 0.0],
+            vec![0.1, 0.0, 0.0, 0.0],
+            vec![0.2, 0.0, 0.0, 0.0],
+            vec![0.3, 0.0, 0.0, 0.0],
+            vec![0.4, 0.0, 0.0, 0.0],
+        ];
+        // 2 true positives, 1 false positive, 3 true negatives, 1 false negative.
+        let targets: Vec<Vec<f32>> = [1.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0]
+            .iter()
+            .map(|&t| vec![t])
+            .collect();
+
+        let matrix = metrics::confusion_matrix(&network, &inputs, &targets, 0.5);
+        assert_eq!(matrix.true_positives, 2);
+        assert_eq!(matrix.false_positives, 1);
+        assert_eq!(matrix.true_negatives, 3);
+        assert_eq!(matrix.false_negatives, 1);
+        assert_eq!(matrix.total(), 7);
+
+        let rendered = matrix.to_string();
+        let rows: Vec<Vec<&str>> = rendered
+            .lines()
+            .map(|line| line.split('|').map(str::trim).collect())
+            .collect();
+        assert_eq!(rows[0], ["Prédit \\ Réel", "Clair", "Précipitation"]);
+        assert_eq!(rows[1], ["Clair", "3", "1"]);
+        assert_eq!(rows[2], ["Précipitation", "1", "2"]);
+
+        let parsed: metrics::ConfusionMatrix = serde_json::from_str(&matrix.to_json()).unwrap();
+        assert_eq!(parsed, matrix);
+    }
+
+    #[test]
+    fn test_impute_missing() {
+        let json = r#"[
+            {"input": {"temp": 10.0, "pressure": 1000.0, "altitude": 100.0, "humidity": 40.0}, "output": true},
+            {"input": {"temp": 20.0, "pressure": 1010.0, "altitude": 200.0, "humidity": null}, "output": false},
+            {"input": {"temp": 30.0, "pressure": 1020.0, "altitude": 300.0, "humidity": 70.0}, "output": true}
+        ]"#;
+        let data: Vec<SimplifiedWeatherDataPoint> = serde_json::from_str(json).unwrap();
+        assert!(data[1].input.humidity.is_nan());
+        assert!(data[1].input.has_missing());
+
+        let imputed = dataset_loader::impute_missing(&data, dataset_loader::ImputeStrategy::Mean);
+        assert_eq!(imputed[1].input.humidity, 55.0);
+        assert_eq!(imputed[1].input.temp, 20.0);
+        assert!(
+            !imputed
+                .iter()
+                .any(|data_point| data_point.input.has_missing())
+        );
+
+        let constant =
+            dataset_loader::impute_missing(&data, dataset_loader::ImputeStrategy::Constant(50.0));
+        assert_eq!(constant[1].input.humidity, 50.0);
+        assert_eq!(constant[0].input.humidity, 40.0);
+    }
+
+    #[test]
+    fn test_weight_diff() {
+        let trainer = BinaryTrainer::new(0.05, 10, 20);
+        let network = trainer.create_weather_network(4, &[3]);
+        assert_eq!(network.weight_diff(&network.clone()), Ok(0.0));
+
+        let mut perturbed = network.clone();
+        perturbed.layers[0].neurons[0].weights[0] += 3.0;
+        perturbed.layers[1].neurons[0].bias -= 4.0;
+        let distance = network.weight_diff(&perturbed).unwrap();
+        assert!((distance - 5.0).abs() < 1e-5);
+
+        let other_shape = trainer.create_weather_network(4, &[4]);
+        assert!(network.weight_diff(&other_shape).is_err());
+    }
+
+    #[test]
+    fn test_label_smoothing() {
+        let mut trainer = BinaryTrainer::new(0.5, 300, 10);
+        assert_eq!(trainer.smoothed_target(1.0), 1.0);
+
+        trainer.label_smoothing = 0.1;
+        assert!((trainer.smoothed_target(1.0) - 0.9).abs() < 1e-6);
+        assert!((trainer.smoothed_target(0.0) - 0.1).abs() < 1e-6);
+
+        let data = clean_temperature_data();
+        let config = InitConfig {
+            seed: Some(4),
+            ..InitConfig::default()
+        };
+        let most_extreme = |trainer: &BinaryTrainer| {
+            let mut network = trainer.create_weather_network_with_config(4, &[], &config);
+            trainer.train(&mut network, &data, &data);
+            data.iter()
+                .map(|data_point| (network.predict_proba(&data_point.input.to_vec()) - 0.5).abs())
+                .fold(0.0f32, f32::max)
+        };
+
+        let smoothed = most_extreme(&trainer);
+        trainer.label_smoothing = 0.0;
+        let hard = most_extreme(&trainer);
+        assert!(
+            smoothed < hard,
+            "Les probabilités lissées devraient être moins extrêmes : {} contre {}",
+            smoothed,
+            hard
+        );
+    }
+
+    #[test]
+    fn test_weather_input_validate() {
+        let mut input = WeatherInput {
+            temp: 22.0,
+            pressure: 1016.0,
+            altitude: 300.0,
+            humidity: 70.0,
+        };
+        assert!(input.validate().is_ok());
+
+        input.humidity = 150.0;
+        input.pressure = 0.0;
+        let violations = input.validate().unwrap_err();
+        assert_eq!(violations.len(), 2);
+        assert!(
+            violations.iter().any(|v| v.starts_with("humidité")),
+            "{:?}",
+            violations
+        );
+        assert!(
+            violations.iter().any(|v| v.starts_with("pression")),
+            "{:?}",
+            violations
+        );
+    }
+
+    #[test]
+    fn test_training_config_round_trip() {
+        let mut trainer = BinaryTrainer::new(0.01, 250, 16);
+        trainer.warmup_epochs = 5;
+        trainer.label_smoothing = 0.05;
+        let training_config = TrainingConfig::new(trainer, 4, vec![6, 3]);
+        let network = training_config.create_network();
+        let normalization_params = [0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0];
+
+        let path = std::env::temp_dir().join("forecast_nn_training_config_test.json");
+        let options = SaveOptions {
+            training_config: Some(training_config.clone()),
+            ..SaveOptions::default()
+        };
+        pickle::save_model_with_options(&network, &normalization_params, &options, &path).unwrap();
+        let saved_model = pickle::load_saved_model(&path).unwrap();
+        assert_eq!(saved_model.network, network);
+        assert_eq!(saved_model.training_config, Some(training_config));
+
+        // Models saved without a configuration still load.
+        pickle::save_model(&network, &normalization_params, &path).unwrap();
+        let saved_model = pickle::load_saved_model(&path).unwrap();
+        assert_eq!(saved_model.training_config, None);
+
+        std::fs::remove_file(&path).unwrap_or(());
+    }
+
+    #[test]
+    fn test_init_output_bias_from_prior() {
+        let trainer = BinaryTrainer::new(0.05, 10, 20);
+        let mut network = trainer.create_weather_network(4, &[3]);
+        let data: Vec<SimplifiedWeatherDataPoint> = (0..8)
+            .map(|i| SimplifiedWeatherDataPoint {
+                input: WeatherInput {
+                    temp: 0.5,
+                    pressure: 0.5,
+                    altitude: 0.5,
+                    humidity: 0.5,
+                },
+                output: i < 2,
+            })
+            .collect();
+
+        let bias = trainer.init_output_bias_from_prior(&mut network, &data);
+        let expected = (0.25f32 / 0.75).ln();
+        assert!((bias - expected).abs() < 1e-6);
+        assert_eq!(network.layers[1].neurons[0].bias, bias);
+    }
+
+    #[test]
+    fn test_knn_classify() {
+        let train = clean_temperature_data();
+        let query = |temp: f32| WeatherInput {
+            temp,
+            pressure: 0.5,
+            altitude: 0.5,
+            humidity: 0.5,
+        };
+
+        for data_point in &train {
+            assert_eq!(
+                baseline::knn_classify(&train, &data_point.input, 1),
+                data_point.output
+            );
+        }
+        assert!(baseline::knn_classify(&train, &query(0.9), 5));
+        assert!(!baseline::knn_classify(&train, &query(0.1), 5));
+    }
+
+    #[test]
+    fn test_train_logistic() {
+        let data: Vec<SimplifiedWeatherDataPoint> = (0..100)
+            .map(|i| {
+                let temp = (i % 10) as f32 / 9.0;
+                let humidity = (i / 10) as f32 / 9.0;
+                SimplifiedWeatherDataPoint {
+                    input: WeatherInput {
+                        temp,
+                        pressure: 0.5,
+                        altitude: 0.5,
+                        humidity,
+                    },
+                    output: humidity - temp > 0.05,
+                }
+            })
+            .collect();
+
+        let network = baseline::train_logistic(&data, 0.5, 200);
+        assert_eq!(network.depth(), 1);
+
+        let inputs = dataset_loader::prepare_inputs(&data);
+        let targets = dataset_loader::prepare_outputs(&data);
+        let accuracy = metrics::binary_accuracy(&network, &inputs, &targets, 0.5);
+        assert!(
+            accuracy > 0.9,
+            "Précision de la régression logistique : {}",
+            accuracy
+        );
+    }
+
+    #[test]
+    fn test_bias_free_neuron() {
+        let mut neuron = Neuron::new(
+            0,
+            "Sortie".to_string(),
+            ActivationFunction::Sigmoid,
+            0.7,
+            vec![0.1, 0.2],
+        );
+        neuron.use_bias = false;
+        assert_eq!(neuron.activate(&[0.0, 0.0]), 0.5);
+
+        let mut network =
+            NeuralNetwork::new(vec![Layer::new(0, "Sortie".to_string(), vec![neuron])]);
+        for _ in 0..50 {
+            network.backward(&[0.3, 0.9], &[1.0], 0.5);
+        }
+        let trained = &network.layers[0].neurons[0];
+        assert_eq!(
+            trained.bias, 0.7,
+            "Le biais d'un neurone sans biais ne doit pas bouger"
+        );
+        assert_ne!(trained.weights, vec![0.1, 0.2]);
+
+        let json = r#"{"id":0,"name":"N","activation_function":"relu","bias":0.0,"weights":[1.0]}"#;
+        let legacy: Neuron = serde_json::from_str(json).unwrap();
+        assert!(legacy.use_bias);
+    }
+
+    #[test]
+    fn test_shared_model_concurrent_predictions() {
+        let model = WeatherModel::new(
+            temperature_threshold_network(),
+            [0.0, 40.0, 950.0, 1050.0, 0.0, 1000.0, 0.0, 100.0],
+        );
+        let shared = SharedModel::new(model);
+        let input = |temp: f32| WeatherInput {
+            temp,
+            pressure: 1016.0,
+            altitude: 300.0,
+            humidity: 70.0,
+        };
+        let expected: Vec<f32> = (0..40)
+            .map(|t| shared.predict_proba(&input(t as f32)))
+            .collect();
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let shared = shared.clone();
+                std::thread::spawn(move || {
+                    (0..40)
+                        .map(|t| shared.predict_proba(&input(t as f32)))
+                        .collect::<Vec<f32>>()
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), expected);
+        }
+        assert!(shared.predict(&input(35.0), 0.5));
+    }
+
+    #[test]
+    fn test_matthews_corrcoef() {
+        let report =
+            metrics::ClassificationReport::from_confusion_matrix(metrics::ConfusionMatrix {
+                true_positives: 6,
+                false_positives: 2,
+                true_negatives: 8,
+                false_negatives: 4,
+            });
+        assert!((report.accuracy - 0.7).abs() < 1e-6);
+        assert!((report.precision - 0.75).abs() < 1e-6);
+        assert!((report.recall - 0.6).abs() < 1e-6);
+
+        // (6*8 - 2*4) / sqrt(8 * 10 * 10 * 12) = 40 / sqrt(9600)
+        let expected = 40.0 / 9600.0f32.sqrt();
+        assert!((metrics::matthews_corrcoef(&report) - expected).abs() < 1e-6);
+
+        let degenerate =
+            metrics::ClassificationReport::from_confusion_matrix(metrics::ConfusionMatrix {
+                true_positives: 5,
+                true_negatives: 0,
+                false_positives: 3,
+                false_negatives: 0,
+            });
+        assert_eq!(metrics::matthews_corrcoef(&degenerate), 0.0);
+    }
+
+    #[test]
+    fn test_log_loss() {
+        let network = temperature_threshold_network();
+        let inputs = vec![vec![0.5, 0.0, 0.0, 0.0], vec![0.7, 0.0, 0.0, 0.0]];
+        let targets = vec![vec![1.0], vec![0.0]];
+
+        // p = 0.5 for the first sample and sigmoid(2) for the second.
+        let expected = (2.0f32.ln() + (1.0 + 2.0f32.exp()).ln()) / 2.0;
+        assert!((metrics::log_loss(&network, &inputs, &targets) - expected).abs() < 1e-5);
+
+        let confident = NeuralNetwork::new(vec![Layer::new(
+            0,
+            "Sortie".to_string(),
+            vec![Neuron::new(
+                0,
+                "Sortie".to_string(),
+                ActivationFunction::Sigmoid,
+                100.0,
+                vec![0.0; 4],
+            )],
+        )]);
+        let loss = metrics::log_loss(&confident, &inputs[..1], &[vec![0.0]]);
+        assert!(loss.is_finite(), "La perte doit rester finie pour p = 1");
+    }
+
+    #[test]
+    fn test_threshold_sweep() {
+        let network = temperature_threshold_network();
+        let data = noisy_temperature_data(0.0);
+        let inputs = dataset_loader::prepare_inputs(&data);
+        let targets = dataset_loader::prepare_outputs(&data);
+        let thresholds = [0.1, 0.3, 0.5, 0.7, 0.9];
+
+        let sweep = metrics::threshold_sweep(&network, &inputs, &targets, &thresholds);
+        assert_eq!(sweep.len(), thresholds.len());
+        for (report, &threshold) in sweep.iter().zip(&thresholds) {
+            assert_eq!(
+                *report,
+                metrics::classification_report(&network, &inputs, &targets, threshold)
+            );
+        }
+        assert!(sweep[0].recall >= sweep[4].recall);
+    }
+
+    #[test]
+    fn test_checkpointing() {
+        let checkpoint_path = std::env::temp_dir().join("forecast_nn_checkpoint_test.json");
+        std::fs::remove_file(&checkpoint_path).unwrap_or(());
+
+        let mut trainer = BinaryTrainer::new(0.5, 60, 10);
+        trainer.checkpoint_path = Some(checkpoint_path.clone());
+        trainer.normalization_params = Some(dataset_loader::IDENTITY_NORMALIZATION);
+        let training_data = noisy_temperature_data(0.0);
+        let validation_data = noisy_temperature_data(0.5);
+        let mut network = trainer.create_weather_network(4, &[4]);
+
+        let best_accuracy = trainer.train(&mut network, &training_data, &validation_data);
+        assert!(
+            checkpoint_path.exists(),
+            "Le point de contrôle devrait être écrit"
+        );
+
+        let checkpoint = pickle::load_saved_model(&checkpoint_path).unwrap();
+        let accuracy = metrics::binary_accuracy(
+            &checkpoint.network,
+            &dataset_loader::prepare_inputs(&validation_data),
+            &dataset_loader::prepare_outputs(&validation_data),
+            0.5,
+        );
+        assert_eq!(accuracy, best_accuracy);
+
+        std::fs::remove_file(&checkpoint_path).unwrap_or(());
+    }
+
+    #[test]
+    fn test_resume_from_checkpoint() {
+        let checkpoint_path = std::env::temp_dir().join("forecast_nn_resume_test.json");
+        let data = clean_temperature_data();
+        let config = InitConfig {
+            seed: Some(2),
+            ..InitConfig::default()
+        };
+
+        let trainer = BinaryTrainer::new(0.5, 300, 10);
+        let mut network = trainer.create_weather_network_with_config(4, &[], &config);
+        trainer.train(&mut network, &data, &data);
+        let inputs = dataset_loader::prepare_inputs(&data);
+        let targets = dataset_loader::prepare_outputs(&data);
+        let checkpoint_accuracy = metrics::binary_accuracy(&network, &inputs, &targets, 0.5);
+        pickle::save_model(
+            &network,
+            &dataset_loader::IDENTITY_NORMALIZATION,
+            &checkpoint_path,
+        )
+        .unwrap();
+
+        let (model, accuracy) = BinaryTrainer::new(0.05, 20, 10)
+            .resume(&checkpoint_path, &data, &data)
+            .unwrap();
+        assert!(
+            accuracy >= checkpoint_accuracy,
+            "Reprise : {} contre {}",
+            accuracy,
+            checkpoint_accuracy
+        );
+        assert_eq!(model.network.depth(), 1);
+
+        std::fs::remove_file(&checkpoint_path).unwrap_or(());
+    }
+
+    #[test]
+    fn test_model_metadata_round_trip() {
+        let path = std::env::temp_dir().join("forecast_nn_metadata_test.json");
+        let network = temperature_threshold_network();
+        let normalization_params = [0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0];
+        let metadata: HashMap<String, String> =
+            [("experiment", "seuil-temperature"), ("commit", "abc1234")]
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect();
+
+        let mut options = SaveOptions {
+            metadata: metadata.clone(),
+            ..SaveOptions::default()
+        };
+        pickle::save_model_with_options(&network, &normalization_params, &options, &path).unwrap();
+        let saved_model = pickle::load_saved_model(&path).unwrap();
+        assert_eq!(saved_model.metadata, metadata);
+        assert_eq!(saved_model.training_config, None);
+
+        // The configuration and the metadata are saved together.
+        options.training_config = Some(TrainingConfig::new(BinaryTrainer::default(), 4, vec![]));
+        pickle::save_model_with_options(&network, &normalization_params, &options, &path).unwrap();
+        let reloaded = pickle::load_saved_model(&path).unwrap();
+        assert_eq!(reloaded.metadata["commit"], "abc1234");
+        assert_eq!(reloaded.training_config, options.training_config);
+
+        std::fs::remove_file(&path).unwrap_or(());
+    }
+
+    #[test]
+    fn test_normalize_batch() {
+        let params = [0.0, 40.0, 950.0, 1050.0, 0.0, 1000.0, 50.0, 50.0];
+        let inputs = vec![
+            WeatherInput {
+                temp: 10.0,
+                pressure: 1000.0,
+                altitude: 250.0,
+                humidity: 50.0,
+            },
+            WeatherInput {
+                temp: 30.0,
+                pressure: 980.0,
+                altitude: 750.0,
+                humidity: 50.0,
+            },
+        ];
+
+        let batch = dataset_loader::normalize_batch(&inputs, &params);
+        for (row, input) in batch.iter().zip(&inputs) {
+            assert_eq!(
+                *row,
+                dataset_loader::normalize_with_params(input, &params).to_vec()
+            );
+        }
+        assert_eq!(batch[0], vec![0.25, 0.5, 0.25, 0.0]);
+        assert!(
+            batch.iter().flatten().all(|value| value.is_finite()),
+            "Une plage nulle ne doit pas produire de NaN"
+        );
+    }
+
+    fn separable_data(count: usize) -> Vec<SimplifiedWeatherDataPoint> {
+        (0..count)
+            .map(|i| {
+                let temp = (i * 37 % 101) as f32 / 100.0;
+                let humidity = (i * 59 % 103) as f32 / 102.0;
+                // Push every point at least 0.1 away from the boundary humidity = temp.
+                let margin = if humidity >= temp { 0.1 } else { -0.1 };
+                let humidity = (humidity + margin).clamp(0.0, 1.0);
+                SimplifiedWeatherDataPoint {
+                    input: WeatherInput {
+                        temp,
+                        pressure: 0.5,
+                        altitude: (i % 7) as f32 / 6.0,
+                        humidity,
+                    },
+                    output: humidity > temp,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_training_pipeline_on_synthetic_data() {
+        let training_data = separable_data(200);
+        let validation_data = separable_data(300)[200..].to_vec();
+        let config = InitConfig {
+            seed: Some(21),
+            ..InitConfig::default()
+        };
+        let mut trainer = BinaryTrainer::new(0.1, 300, 10);
+        trainer.seed = Some(7);
+
+        let mut network = trainer.create_weather_network_with_config(4, &[8, 4], &config);
+        let accuracy = trainer.train(&mut network, &training_data, &validation_data);
+        assert!(
+            accuracy > 0.95,
+            "Précision sur données séparables : {}",
+            accuracy
+        );
+
+        let mut replay = trainer.create_weather_network_with_config(4, &[8, 4], &config);
+        trainer.train(&mut replay, &training_data, &validation_data);
+        assert_eq!(
+            replay, network,
+            "Un entraînement avec graines doit être reproductible"
+        );
+    }
+
+    #[test]
+    fn test_focal_loss() {
+        let focal = LossFunction::FocalLoss { gamma: 2.0 };
+        let bce = LossFunction::BinaryCrossEntropy;
+
+        // An easy example: the target class already gets probability 0.95.
+        let focal_gradient = focal.gradient(0.95, 1.0);
+        let bce_gradient = bce.gradient(0.95, 1.0);
+        assert!(focal_gradient < 0.0 && bce_gradient < 0.0);
+        assert!(
+            focal_gradient.abs() < 0.05 * bce_gradient.abs(),
+            "le gradient focal devrait être bien plus faible sur un exemple facile"
+        );
+        let cross_entropy_focal = LossFunction::FocalLoss { gamma: 0.0 };
+        assert!((cross_entropy_focal.loss(0.3, 0.0) - bce.loss(0.3, 0.0)).abs() < 1e-6);
+
+        for &(output, target) in &[(0.3f32, 1.0f32), (0.8, 0.0), (0.6, 1.0)] {
+            let epsilon = 1e-3;
+            let numerical = (focal.loss(output + epsilon, target)
+                - focal.loss(output - epsilon, target))
+                / (2.0 * epsilon);
+            assert!(
+                (numerical - focal.gradient(output, target)).abs() < 1e-2,
+                "gradient focal incorrect"
+            );
+        }
+
+        let mut trainer = BinaryTrainer::new(0.5, 100, 10);
+        trainer.loss_function = focal;
+        trainer.seed = Some(1);
+        let data = clean_temperature_data();
+        let mut network = trainer.create_weather_network(4, &[]);
+        assert!(trainer.train(&mut network, &data, &data) > 0.9);
+    }
+
+    #[test]
+    fn test_gradient_norm_reporting() {
+        // A linear network outputting exactly 1.0 fits a target of 1.0 perfectly.
+        let neuron = Neuron::new(
+            0,
+            "Sortie".to_string(),
+            ActivationFunction::Linear,
+            1.0,
+            vec![0.0; 4],
+        );
+        let mut network =
+            NeuralNetwork::new(vec![Layer::new(0, "Sortie".to_string(), vec![neuron])]);
+        let mut buffers = ForwardBuffers::for_network(&network);
+        let inputs = [0.5, 0.2, 0.1, 0.3];
+
+        let (loss, norm) = network.backward_with_gradient_norm(
+            &inputs,
+            &[1.0],
+            0.1,
+            LossFunction::MeanSquaredError,
+            &mut buffers,
+        );
+        assert_eq!(loss, 0.0);
+        assert_eq!(
+            norm, 0.0,
+            "la norme devrait être nulle pour un ajustement parfait"
+        );
+
+        let (_, norm) = network.backward_with_gradient_norm(
+            &inputs,
+            &[0.0],
+            0.1,
+            LossFunction::MeanSquaredError,
+            &mut buffers,
+        );
+        assert!(norm > 0.0);
+
+        let mut trainer = BinaryTrainer::new(0.5, 30, 10);
+        trainer.seed = Some(3);
+        let data = noisy_temperature_data(0.0);
+        let mut network = trainer.create_weather_network(4, &[3]);
+        let (accuracy, history) = trainer.train_with_history(&mut network, &data, &data);
+        assert!(!history.is_empty() && history.len() <= 30);
+        assert!(history.iter().all(|stats| stats.gradient_norm >= 0.0));
+        assert_eq!(
+            history
+                .iter()
+                .map(|stats| stats.validation_accuracy)
+                .fold(0.0, f32::max),
+            accuracy
+        );
+    }
+
+    #[test]
+    fn test_denormalize_output() {
+        let params = [-10.0, 40.0, 950.0, 1050.0, 0.0, 2000.0, 0.0, 100.0];
+        let input = WeatherInput {
+            temp: 17.5,
+            pressure: 1000.0,
+            altitude: 500.0,
+            humidity: 50.0,
+        };
+
+        let normalized = dataset_loader::normalize_with_params(&input, &params);
+        assert!((normalized.temp - 0.55).abs() < 1e-6);
+        let temperature = dataset_loader::denormalize_output(normalized.temp, &params);
+        assert!(
+            (temperature - input.temp).abs() < 1e-4,
+            "la température d'origine devrait être retrouvée"
+        );
+    }
+
+    #[test]
+    fn test_load_dataset_gz() {
+        use flate2::Compression;
+        use flate2::write::GzEncoder;
+        use std::io::Write;
+
+        let json = std::fs::read("weather-test-dataset.json").expect("Jeu de test absent");
+        let gz_path = std::env::temp_dir().join("forecast_nn_gz_test.json.gz");
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&json).unwrap();
+        std::fs::write(&gz_path, encoder.finish().unwrap()).unwrap();
+
+        let compressed =
+            dataset_loader::load_dataset_gz(&gz_path).expect("Échec du chargement gzip");
+        let plain = dataset_loader::load_dataset("weather-test-dataset.json").unwrap();
+        assert_eq!(
+            serde_json::to_string(&compressed).unwrap(),
+            serde_json::to_string(&plain).unwrap(),
+            "Le fichier compressé devrait donner le même jeu de données"
+        );
+
+        std::fs::remove_file(&gz_path).unwrap_or(());
+    }
+
+    #[test]
+    fn test_normalize_inputs_range() {
+        let data = noisy_temperature_data(0.0);
+        let (normalized, params) = dataset_loader::normalize_inputs_range(&data, -1.0, 1.0);
+
+        let temps: Vec<f32> = normalized.iter().map(|point| point.input.temp).collect();
+        let min = temps.iter().copied().fold(f32::MAX, f32::min);
+        let max = temps.iter().copied().fold(f32::MIN, f32::max);
+        assert!((min + 1.0).abs() < 1e-6, "le minimum devrait valoir -1");
+        assert!((max - 1.0).abs() < 1e-6, "le maximum devrait valoir 1");
+
+        let reproduced =
+            dataset_loader::normalize_with_params_range(&data[7].input, &params, -1.0, 1.0);
+        assert!((reproduced.temp - normalized[7].input.temp).abs() < 1e-6);
+        assert!((reproduced.humidity - normalized[7].input.humidity).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_class_balance() {
+        let data: Vec<SimplifiedWeatherDataPoint> = (0..55)
+            .map(|i| SimplifiedWeatherDataPoint {
+                input: WeatherInput {
+                    temp: i as f32 / 55.0,
+                    pressure: 0.5,
+                    altitude: 0.5,
+                    humidity: 0.5,
+                },
+                output: i % 11 == 0,
+            })
+            .collect();
+
+        let (positive, negative, ratio) = dataset_loader::class_balance(&data);
+        assert_eq!((positive, negative), (5, 50));
+        assert!((ratio - 10.0).abs() < 1e-6);
+
+        let warning = trainer::imbalance_warning(&data).expect("un avertissement est attendu");
+        assert!(warning.contains("10.0:1"), "{}", warning);
+        assert!(trainer::imbalance_warning(&noisy_temperature_data(0.0)).is_none());
+    }
+
+    #[test]
+    fn test_create_weather_network_with_outputs() {
+        let trainer = BinaryTrainer::default();
+        let network =
+            trainer.create_weather_network_with_outputs(4, &[6], 3, &InitConfig::default());
+
+        let output_layer = network.layers.last().unwrap();
+        assert_eq!(
+            output_layer.neurons.len(),
+            3,
+            "Attendu 3 neurones de sortie"
+        );
+        assert!(
+            output_layer
+                .neurons
+                .iter()
+                .all(|neuron| neuron.weights.len() == 6)
+        );
+        assert_eq!(
+            network
+                .activate(&[0.1, 0.2, 0.3, 0.4])
+                .last()
+                .unwrap()
+                .len(),
+            3
+        );
+
+        let single = trainer.create_weather_network(4, &[6]);
+        assert_eq!(single.layers.last().unwrap().neurons.len(), 1);
+    }
+
+    #[test]
+    fn test_he_uniform_initialization() {
+        let fan_in = 50;
+        let bound = (6.0f32 / fan_in as f32).sqrt();
+        let config = InitConfig {
+            scheme: InitScheme::HeUniform,
+            seed: Some(11),
+            ..InitConfig::default()
+        };
+        let mut rng = config.rng();
+
+        let weights: Vec<f32> = (0..20_000)
+            .map(|_| config.sample_weight(fan_in, 10, &mut rng))
+            .collect();
+        assert!(
+            weights.iter().all(|weight| weight.abs() <= bound),
+            "les poids devraient rester dans ±sqrt(6 / fan_in)"
+        );
+
+        let mean = weights.iter().sum::<f32>() / weights.len() as f32;
+        let variance = weights
+            .iter()
+            .map(|weight| (weight - mean).powi(2))
+            .sum::<f32>()
+            / weights.len() as f32;
+        let expected = 2.0 / fan_in as f32;
+        assert!(mean.abs() < 0.01);
+        assert!(
+            (variance - expected).abs() < 0.05 * expected,
+            "variance {} au lieu de {}",
+            variance,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_mixup() {
+        let data = noisy_temperature_data(0.0);
+        let mixed = dataset_loader::mixup(&data, 0.4, 5).expect("Échec du mixup");
+        assert_eq!(mixed.len(), data.len());
+
+        for (i, (features, label)) in mixed.iter().enumerate() {
+            let x_i = data[i].input.to_vec();
+            let y_i = if data[i].output { 1.0 } else { 0.0 };
+
+            // Some partner j and weight λ in [0, 1] must explain every feature and the label.
+            let is_convex_combination = data.iter().any(|other| {
+                let x_j = other.input.to_vec();
+                let y_j = if other.output { 1.0 } else { 0.0 };
+                let lambda = if (x_i[0] - x_j[0]).abs() > 1e-6 {
+                    (features[0] - x_j[0]) / (x_i[0] - x_j[0])
+                } else {
+                    1.0
+                };
+                (-1e-4..=1.0 + 1e-4).contains(&lambda)
+                    && x_i
+                        .iter()
+                        .zip(&x_j)
+                        .zip(features)
+                        .all(|((a, b), x)| (lambda * a + (1.0 - lambda) * b - x).abs() < 1e-4)
+                    && (lambda * y_i + (1.0 - lambda) * y_j - label).abs() < 1e-4
+            });
+            assert!(
+                is_convex_combination,
+                "l'exemple {} n'est pas une combinaison convexe",
+                i
+            );
+        }
+
+        assert!(dataset_loader::mixup(&data, 0.0, 5).is_err());
+    }
+
+    #[test]
+    fn test_predict_dataset() {
+        let params = [0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0];
+        let input = |temp| WeatherInput {
+            temp,
+            pressure: 0.5,
+            altitude: 0.5,
+            humidity: 0.5,
+        };
+        let inputs = [input(0.9), input(0.1)];
+
+        let predictions =
+            weather_model::predict_dataset(&temperature_threshold_network(), &params, &inputs);
+        let json: serde_json::Value = serde_json::to_value(&predictions).unwrap();
+
+        let array = json.as_array().expect("un tableau JSON est attendu");
+        assert_eq!(array.len(), 2);
+        assert!(array[0]["probability"].as_f64().unwrap() > 0.9);
+        assert_eq!(array[0]["label"], true);
+        assert_eq!(array[1]["label"], false);
+        assert!((array[1]["input"]["temp"].as_f64().unwrap() - 0.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_early_stopping_on_validation_loss() {
+        // The data is separable: accuracy saturates early while the loss keeps decreasing.
+        let data = clean_temperature_data();
+        let mut trainer = BinaryTrainer::new(0.5, 120, 10);
+        trainer.seed = Some(2);
+        let init_config = InitConfig {
+            seed: Some(2),
+            ..InitConfig::default()
+        };
+
+        let mut network = trainer.create_weather_network_with_config(4, &[], &init_config);
+        let (_, accuracy_history) = trainer.train_with_history(&mut network, &data, &data);
+        assert!(
+            accuracy_history.len() < 120,
+            "la précision stagnante devrait arrêter l'entraînement"
+        );
+
+        trainer.monitor = StopMetric::Loss;
+        let mut network = trainer.create_weather_network_with_config(4, &[], &init_config);
+        let (_, loss_history) = trainer.train_with_history(&mut network, &data, &data);
+        assert_eq!(
+            loss_history.len(),
+            120,
+            "la perte qui diminue devrait prolonger l'entraînement"
+        );
+    }
+
+    #[test]
+    fn test_cyclical_learning_rate() {
+        let schedule = LrSchedule::Cyclical {
+            base: 0.01,
+            max: 0.1,
+            step_size: 5,
+        };
+
+        assert!((schedule.rate_at(0.05, 0) - 0.01).abs() < 1e-6);
+        assert!(
+            (schedule.rate_at(0.05, 5) - 0.1).abs() < 1e-6,
+            "le maximum est atteint au milieu du cycle"
+        );
+        assert!(
+            (schedule.rate_at(0.05, 10) - 0.01).abs() < 1e-6,
+            "retour à la base en fin de cycle"
+        );
+        assert!((schedule.rate_at(0.05, 15) - 0.1).abs() < 1e-6);
+        assert!((schedule.rate_at(0.05, 2) - schedule.rate_at(0.05, 8)).abs() < 1e-6);
+        assert_eq!(LrSchedule::Constant.rate_at(0.05, 7), 0.05);
+
+        let mut trainer = BinaryTrainer::new(0.05, 20, 10);
+        trainer.lr_schedule = schedule;
+        assert!((trainer.learning_rate_at(5) - 0.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_layer_new_mixed() {
+        let layer = Layer::new_mixed(
+            0,
+            "Mixte".to_string(),
+            &[
+                (ActivationFunction::Relu, vec![1.0, -1.0]),
+                (ActivationFunction::Tanh, vec![1.0, -1.0]),
+            ],
+        );
+
+        assert_eq!(
+            layer.get_neuron_activation_functions(),
+            vec![ActivationFunction::Relu, ActivationFunction::Tanh]
+        );
+        assert_eq!(layer.get_neuron_names(), vec!["Mixte_0", "Mixte_1"]);
+
+        // The weighted sum is -0.5: relu clips it to 0, tanh keeps its sign.
+        let outputs = layer.activate(&[0.5, 1.0]);
+        assert_eq!(outputs[0], 0.0);
+        assert!((outputs[1] - (-0.5f32).tanh()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_jacobian() {
+        let weights = [vec![0.5, -1.5], vec![2.0, 0.25]];
+        let neurons = weights
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                Neuron::new(
+                    i as u32,
+                    format!("Sortie_{}", i),
+                    ActivationFunction::Linear,
+                    0.1,
+                    row.clone(),
+                )
+            })
+            .collect();
+        let network = NeuralNetwork::new(vec![Layer::new(0, "Sortie".to_string(), neurons)]);
+
+        let jacobian = network.jacobian(&[0.3, -0.7]);
+        assert_eq!(jacobian.len(), 2);
+        for (row, expected) in jacobian.iter().zip(&weights) {
+            for (value, weight) in row.iter().zip(expected) {
+                assert!(
+                    (value - weight).abs() < 1e-6,
+                    "la jacobienne devrait égaler les poids"
+                );
+            }
+        }
+
+        let classifier = temperature_threshold_network();
+        let input = [0.4, 0.1, 0.2, 0.3];
+        assert_eq!(
+            classifier.jacobian(&input),
+            vec![classifier.input_gradient(&input)]
+        );
+    }
+
+    #[test]
+    fn test_load_model_from_bytes() {
+        let network = temperature_threshold_network();
+        let params = [0.0, 40.0, 950.0, 1050.0, 0.0, 2000.0, 0.0, 100.0];
+        let model_path = std::env::temp_dir().join("forecast_nn_bytes_test.json");
+        pickle::save_model(&network, &params, &model_path).unwrap();
+        let bytes = std::fs::read(&model_path).unwrap();
+        std::fs::remove_file(&model_path).unwrap_or(());
+
+        let (loaded_network, loaded_params) =
+            pickle::load_model_from_bytes(&bytes).expect("Échec du chargement depuis la mémoire");
+        assert_eq!(loaded_network, network);
+        assert_eq!(loaded_params, params);
+        let saved_model = pickle::load_saved_model_from_bytes(&bytes).unwrap();
+        assert_eq!(saved_model.network, network);
+
+        assert!(pickle::load_model_from_bytes(b"pas du json").is_err());
+    }
+
+    #[test]
+    fn test_inclusive_bound_at_threshold() {
+        // A linear output of exactly 0.5 sits on the default threshold.
+        let model = constant_model(0.5);
+        let input = WeatherInput {
+            temp: 20.0,
+            pressure: 1015.0,
+            altitude: 300.0,
+            humidity: 60.0,
+        };
+        assert_eq!(model.predict_proba(&input), 0.5);
+
+        assert!(model.predict(&input, 0.5));
+        assert!(model.predict_with_bound(&input, 0.5, InclusiveBound::AtLeast));
+        assert!(!model.predict_with_bound(&input, 0.5, InclusiveBound::GreaterThan));
+
+        let inputs = vec![vec![0.5; 4]];
+        let targets = vec![vec![1.0]];
+        let accuracy = |bound| {
+            metrics::binary_accuracy_with_bound(&model.network, &inputs, &targets, 0.5, bound)
+        };
+        assert_eq!(accuracy(InclusiveBound::AtLeast), 1.0);
+        assert_eq!(accuracy(InclusiveBound::GreaterThan), 0.0);
+    }
+
+    #[test]
+    fn test_adamw_decoupled_weight_decay() {
+        let learning_rate = 0.1;
+        let mut optimizer = AdamWOptimizer::new(0.9, 0.999, 1e-8, 0.5);
+        let mut parameters = vec![2.0, -1.0, 0.5];
+        let initial = parameters.clone();
+
+        for step in 1..=5 {
+            optimizer.step(&mut parameters, &[0.0; 3], learning_rate);
+            let factor = (1.0f32 - learning_rate * 0.5).powi(step);
+            for (parameter, start) in parameters.iter().zip(&initial) {
+                assert!(
+                    (parameter - start * factor).abs() < 1e-6,
+                    "les poids devraient décroître géométriquement"
+                );
+            }
+        }
+
+        // With a gradient, the first bias-corrected step moves each parameter by about `learning_rate`.
+        let mut optimizer = AdamWOptimizer::new(0.9, 0.999, 1e-8, 0.0);
+        let mut parameters = vec![1.0];
+        optimizer.step(&mut parameters, &[3.0], learning_rate);
+        assert!((parameters[0] - 0.9).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_save_and_load_weights() {
+        let trainer = BinaryTrainer::default();
+        let source = trainer.create_weather_network(4, &[5, 3]);
+        let weights_path = std::env::temp_dir().join("forecast_nn_weights_test.json");
+        pickle::save_weights(&source, &weights_path).expect("Échec de la sauvegarde des poids");
+
+        let mut target = trainer.create_weather_network(4, &[5, 3]);
+        pickle::load_weights_into(&mut target, &weights_path)
+            .expect("Échec du chargement des poids");
+        let input = [0.2, 0.4, 0.6, 0.8];
+        assert_eq!(target.predict_proba(&input), source.predict_proba(&input));
+
+        let mut other_shape = trainer.create_weather_network(4, &[3, 5]);
+        assert!(
+            pickle::load_weights_into(&mut other_shape, &weights_path).is_err(),
+            "une architecture différente devrait être refusée"
+        );
+
+        std::fs::remove_file(&weights_path).unwrap_or(());
+    }
+
+    #[test]
+    fn test_make_windows() {
+        let series: Vec<WeatherInput> = (0..5)
+            .map(|step| WeatherInput {
+                temp: step as f32,
+                pressure: 1000.0 + step as f32,
+                altitude: 300.0,
+                humidity: 50.0,
+            })
+            .collect();
+
+        let windows = dataset_loader::make_windows(&series, 2);
+        assert_eq!(
+            windows.len(),
+            3,
+            "chaque fenêtre doit avoir une étape suivante"
+        );
+        assert!(windows.iter().all(|window| window.len() == 8));
+        assert_eq!(windows[1][0], 1.0);
+        assert_eq!(windows[1][4], 2.0);
+
+        assert!(dataset_loader::make_windows(&series, 5).is_empty());
+    }
+
+    #[test]
+    fn test_cosine_annealing_learning_rate() {
+        let schedule = LrSchedule::CosineAnnealing {
+            max_epochs: 50,
+            eta_min: 0.001,
+        };
+
+        assert!((schedule.rate_at(0.1, 0) - 0.1).abs() < 1e-6);
+        assert!(
+            (schedule.rate_at(0.1, 25) - 0.0505).abs() < 1e-6,
+            "la moitié du chemin à mi-parcours"
+        );
+        assert!((schedule.rate_at(0.1, 50) - 0.001).abs() < 1e-6);
+        assert!((schedule.rate_at(0.1, 80) - 0.001).abs() < 1e-6);
+        assert!(schedule.rate_at(0.1, 10) > schedule.rate_at(0.1, 20));
+    }
+
+    #[test]
+    fn test_robust_scale_inputs() {
+        let mut data: Vec<SimplifiedWeatherDataPoint> = (0..21)
+            .map(|i| SimplifiedWeatherDataPoint {
+                input: WeatherInput {
+                    temp: 10.0 + i as f32,
+                    pressure: 1000.0 + i as f32,
+                    altitude: 300.0,
+                    humidity: 50.0,
+                },
+                output: i % 2 == 0,
+            })
+            .collect();
+        // One extreme pressure reading.
+        data[20].input.pressure = 100_000.0;
+
+        let (scaled, params) = dataset_loader::robust_scale_inputs(&data);
+        assert_eq!(params.medians[1], 1010.0);
+        assert_eq!(params.iqrs[1], 10.0);
+        assert!(
+            scaled[..20]
+                .iter()
+                .all(|point| point.input.pressure.abs() <= 1.0),
+            "la valeur aberrante ne devrait pas écraser les autres pressions"
+        );
+        assert!(scaled[20].input.pressure > 1000.0);
+
+        // The constant altitude has a zero IQR and is only centered.
+        assert!(scaled.iter().all(|point| point.input.altitude == 0.0));
+
+        let reproduced = dataset_loader::robust_scale_with_params(&data[3].input, &params);
+        assert_eq!(reproduced.temp, scaled[3].input.temp);
+    }
+
+    #[test]
+    fn test_predict_proba_batch() {
+        let network = BinaryTrainer::default().create_weather_network(4, &[6, 3]);
+        let inputs: Vec<Vec<f32>> = (0..10)
+            .map(|i| {
+                let x = i as f32 / 10.0;
+                vec![x, 1.0 - x, x * x, 0.5]
+            })
+            .collect();
+
+        let probabilities = network.predict_proba_batch(&inputs);
+        assert_eq!(probabilities.len(), inputs.len());
+        for (probability, input) in probabilities.iter().zip(&inputs) {
+            assert_eq!(*probability, network.predict_proba(input));
+        }
+        assert!(network.predict_proba_batch(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_normalize_inputs_or_skip() {
+        let data = noisy_temperature_data(0.0);
+
+        let (passthrough, params) = dataset_loader::normalize_inputs_or_skip(&data, true);
+        assert_eq!(params, dataset_loader::IDENTITY_NORMALIZATION);
+        for (original, kept) in data.iter().zip(&passthrough) {
+            assert_eq!(original.input.to_vec(), kept.input.to_vec());
+            assert_eq!(
+                dataset_loader::normalize_with_params(&original.input, &params).to_vec(),
+                original.input.to_vec(),
+                "les paramètres identité ne devraient rien changer"
+            );
+        }
+
+        let (_, computed) = dataset_loader::normalize_inputs_or_skip(&data, false);
+        assert_eq!(computed, dataset_loader::normalize_inputs(&data).1);
+    }
+
+    #[test]
+    fn test_l1_activation_sparsity() {
+        let data = clean_temperature_data();
+        let init_config = InitConfig {
+            seed: Some(9),
+            ..InitConfig::default()
+        };
+        let inactive_units = |lambda: f32| {
+            let mut trainer = BinaryTrainer::new(0.2, 60, 10);
+            trainer.seed = Some(9);
+            trainer.l1_activation_lambda = lambda;
+            trainer.l1_activation_layer = 0;
+            let mut network = trainer.create_weather_network_with_config(4, &[8], &init_config);
+            trainer.train(&mut network, &data, &data);
+
+            data.iter()
+                .map(|point| {
+                    network.activate(&point.input.to_vec())[0]
+                        .iter()
+                        .filter(|&&activation| activation == 0.0)
+                        .count()
+                })
+                .sum::<usize>()
+        };
+
+        let dense = inactive_units(0.0);
+        let sparse = inactive_units(0.05);
+        assert!(
+            sparse > dense,
+            "la pénalité L1 devrait éteindre plus d'unités ({} contre {})",
+            sparse,
+            dense
+        );
+    }
+
+    #[test]
+    fn test_per_class_accuracy() {
+        // The model always predicts clear skies on a mostly clear set.
+        let network = constant_model(0.1).network;
+        let inputs = vec![vec![0.5; 4]; 20];
+        let targets: Vec<Vec<f32>> = (0..20)
+            .map(|i| vec![if i < 2 { 1.0 } else { 0.0 }])
+            .collect();
+
+        let (clear, precipitation) = metrics::per_class_accuracy(&network, &inputs, &targets, 0.5);
+        assert!(
+            (clear - 1.0).abs() < 1e-6,
+            "tous les jours clairs sont reconnus"
+        );
+        assert!(
+            precipitation.abs() < 1e-6,
+            "aucune précipitation n'est reconnue"
+        );
+        assert!((metrics::binary_accuracy(&network, &inputs, &targets, 0.5) - 0.9).abs() < 1e-6);
+    }
+
+    struct CapturingLogger {
+        records: std::sync::Mutex<Vec<(log::Level, String)>>,
+    }
+
+    impl log::Log for CapturingLogger {
+        fn enabled(&self, _metadata: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            self.records
+                .lock()
+                .unwrap()
+                .push((record.level(), record.args().to_string()));
+        }
+
+        fn flush(&self) {}
+    }
+
+    static CAPTURING_LOGGER: CapturingLogger = CapturingLogger {
+        records: std::sync::Mutex::new(Vec::new()),
+    };
+
+    #[test]
+    fn test_training_logs_epoch_summaries() {
+        // Other tests may log concurrently, so only look for this run's 7-epoch summary.
+        log::set_logger(&CAPTURING_LOGGER).expect("un seul test installe le logger");
+        log::set_max_level(log::LevelFilter::Debug);
+
+        let trainer = BinaryTrainer::new(0.5, 7, 10);
+        let data = noisy_temperature_data(0.0);
+        let mut network = trainer.create_weather_network(4, &[]);
+        trainer.train(&mut network, &data, &data);
+
+        let records = CAPTURING_LOGGER.records.lock().unwrap();
+        assert!(
+            records
+                .iter()
+                .any(|(level, message)| *level == log::Level::Info
+                    && message.starts_with("Époque 1/7 :")),
+            "un résumé d'époque devrait être émis au niveau info"
+        );
+        assert!(
+            records
+                .iter()
+                .any(|(level, message)| *level == log::Level::Debug
+                    && message.starts_with("Époque 2/7 :"))
+        );
+    }
+
+    #[test]
+    fn test_most_uncertain() {
+        // The threshold network is exactly uncertain at temp = 0.5.
+        let network = temperature_threshold_network();
+        let data = noisy_temperature_data(0.0);
+
+        let uncertain = metrics::most_uncertain(&network, &data, 5);
+        assert_eq!(uncertain.len(), 5);
+
+        let distance = |point: &SimplifiedWeatherDataPoint| {
+            (network.predict_proba(&point.input.to_vec()) - 0.5).abs()
+        };
+        let worst_selected = uncertain
+            .iter()
+            .map(|point| distance(point))
+            .fold(0.0, f32::max);
+        let selected_temps: Vec<f32> = uncertain.iter().map(|point| point.input.temp).collect();
+        for point in &data {
+            if !selected_temps.contains(&point.input.temp) {
+                assert!(
+                    distance(point) >= worst_selected,
+                    "un exemple plus incertain a été oublié"
+                );
+            }
+        }
+        assert!(
+            uncertain
+                .windows(2)
+                .all(|pair| distance(pair[0]) <= distance(pair[1]))
+        );
+        assert_eq!(
+            metrics::most_uncertain(&network, &data, 1000).len(),
+            data.len()
+        );
+    }
+
+    #[test]
+    fn test_weight_distribution_matches_variance() {
+        let (fan_in, fan_out) = (30, 20);
+        let expected = InitScheme::XavierUniform.variance(fan_in, fan_out);
+        let empirical_variance = |distribution| {
+            let config = InitConfig {
+                distribution: Some(distribution),
+                seed: Some(4),
+                ..InitConfig::default()
+            };
+            let mut rng = config.rng();
+            let weights: Vec<f32> = (0..20_000)
+                .map(|_| config.sample_weight(fan_in, fan_out, &mut rng))
+                .collect();
+            let mean = weights.iter().sum::<f32>() / weights.len() as f32;
+            let variance =
+                weights.iter().map(|w| (w - mean).powi(2)).sum::<f32>() / weights.len() as f32;
+            (variance, weights)
+        };
+
+        let (uniform_variance, uniform_weights) = empirical_variance(WeightDistribution::Uniform);
+        let (normal_variance, normal_weights) = empirical_variance(WeightDistribution::Normal);
+        assert!((uniform_variance - expected).abs() < 0.05 * expected);
+        assert!((normal_variance - expected).abs() < 0.05 * expected);
+        assert!(
+            (uniform_variance - normal_variance).abs() < 0.05 * expected,
+            "les deux distributions devraient avoir la même variance"
+        );
+
+        let bound = (3.0 * expected).sqrt();
+        assert!(uniform_weights.iter().all(|w| w.abs() <= bound));
+        assert!(
+            normal_weights.iter().any(|w| w.abs() > bound),
+            "la loi normale n'est pas bornée"
+        );
+    }
+
+    #[test]
+    fn test_model_toml_round_trip() {
+        let mut trainer = BinaryTrainer::new(0.05, 10, 20);
+        trainer.loss_function = LossFunction::FocalLoss { gamma: 2.0 };
+        let training_config = TrainingConfig::new(trainer, 4, vec![3]);
+        let network = training_config.create_network();
+        let params = [0.0, 40.0, 950.0, 1050.0, 0.0, 2000.0, 0.0, 100.0];
+        let options = SaveOptions {
+            training_config: Some(training_config),
+            metadata: HashMap::from([("station".to_string(), "Lyon".to_string())]),
+            ..SaveOptions::default()
+        };
+
+        let toml_path = std::env::temp_dir().join("forecast_nn_toml_test.toml");
+        pickle::save_model_toml_with_options(&network, &params, &options, &toml_path)
+            .expect("Échec de l'écriture TOML");
+        let text = std::fs::read_to_string(&toml_path).unwrap();
+        assert!(text.contains("[[network.layers]]"), "{}", text);
+
+        let loaded = pickle::load_saved_model_toml(&toml_path).expect("Échec de la lecture TOML");
+        assert_eq!(loaded.network, network);
+        assert_eq!(loaded.normalization_params, params);
+        assert_eq!(loaded.training_config, options.training_config);
+        assert_eq!(loaded.metadata, options.metadata);
+
+        pickle::save_model_toml(&network, &params, &toml_path).unwrap();
+        let (loaded_network, loaded_params) = pickle::load_model_toml(&toml_path).unwrap();
+        assert_eq!(loaded_network, network);
+        assert_eq!(loaded_params, params);
+
+        std::fs::remove_file(&toml_path).unwrap_or(());
+    }
+
+    #[test]
+    fn test_backward_no_update_then_apply_matches_backward() {
+        let output = Layer::new(
+            0,
+            "Sortie".to_string(),
+            vec![Neuron::new(
+                0,
+                "Sortie".to_string(),
+                ActivationFunction::Sigmoid,
+                0.05,
+                vec![0.7, -0.6],
+            )],
+        );
+        let network = NeuralNetwork::new(vec![output]);
+        let (input, target) = ([0.3, 0.9], [1.0]);
+
+        let mut updated = network.clone();
+        let backward_loss = updated.backward(&input, &target, 0.5);
+
+        let (loss, gradients) =
+            network.backward_no_update(&input, &target, LossFunction::MeanSquaredError);
+        assert_eq!(loss, backward_loss);
+        assert_eq!(gradients.values.len(), network.get_parameters().len());
+
+        let mut manual = network.clone();
+        manual.apply_gradients(&gradients, 0.5);
+        for (manual, updated) in manual.get_parameters().iter().zip(updated.get_parameters()) {
+            assert!((manual - updated).abs() < 1e-6, "{} != {}", manual, updated);
+        }
+
+        let mut accumulated = Gradients::default();
+        accumulated.accumulate(&gradients);
+        accumulated.accumulate(&gradients);
+        accumulated.scale(0.5);
+        assert_eq!(accumulated, gradients);
+        accumulated.reset();
+        assert_eq!(accumulated.values.len(), gradients.values.len());
+        assert_eq!(accumulated.norm(), 0.0);
+    }
+
+    #[test]
+    fn test_apply_scaler_rejects_inverted_params() {
+        let inputs = vec![WeatherInput {
+            temp: 20.0,
+            pressure: 1000.0,
+            altitude: 500.0,
+            humidity: 50.0,
+        }];
+        let params = [0.0, 40.0, 950.0, 1050.0, 0.0, 1000.0, 0.0, 100.0];
+
+        let scaled = dataset_loader::apply_scaler(&inputs, &params).unwrap();
+        assert_eq!(scaled, dataset_loader::normalize_batch(&inputs, &params));
+
+        let inverted = [40.0, 0.0, 950.0, 1050.0, 0.0, 1000.0, 0.0, 100.0];
+        let error = dataset_loader::apply_scaler(&inputs, &inverted).unwrap_err();
+        assert!(
+            error.to_string().contains("pour la température"),
+            "{}",
+            error
+        );
+
+        let mut constant = params;
+        constant[7] = constant[6];
+        let error = dataset_loader::apply_scaler(&inputs, &constant).unwrap_err();
+        assert!(error.to_string().contains("pour l'humidité"), "{}", error);
+    }
+
+    #[test]
+    fn test_min_epochs_delays_early_stopping() {
+        // Accuracy saturates on separable data, so the patience alone stops training early.
+        let data = clean_temperature_data();
+        let mut trainer = BinaryTrainer::new(0.5, 120, 10);
+        trainer.seed = Some(2);
+        let init_config = InitConfig {
+            seed: Some(2),
+            ..InitConfig::default()
+        };
+
+        let mut network = trainer.create_weather_network_with_config(4, &[], &init_config);
+        let (_, history) = trainer.train_with_history(&mut network, &data, &data);
+        assert!(history.len() < 80, "{} époques", history.len());
+
+        trainer.min_epochs = 80;
+        let mut network = trainer.create_weather_network_with_config(4, &[], &init_config);
+        let (_, history) = trainer.train_with_history(&mut network, &data, &data);
+        assert!(
+            history.len() > 80,
+            "l'arrêt anticipé ne doit pas se déclencher avant min_epochs ({} époques)",
+            history.len()
+        );
+    }
+
+    #[test]
+    fn test_merge_datasets_warns_on_disjoint_ranges() {
+        let celsius = vec![forecast_point("Ensoleillé"), forecast_point("Pluie")];
+        let mut fahrenheit = vec![forecast_point("Nuageux"), forecast_point("Orage")];
+        for (data_point, temp) in fahrenheit.iter_mut().zip([68.0, 95.0]) {
+            data_point.input.temp = temp;
+        }
+
+        let merged = dataset_loader::merge_datasets(celsius.clone(), celsius.clone());
+        assert_eq!(merged.len(), 4);
+
+        let (merged, warnings) = dataset_loader::merge_datasets_checked(celsius, fahrenheit);
+        assert_eq!(merged.len(), 4);
+        assert_eq!(merged[2].output.forecast, "Nuageux");
+        assert_eq!(warnings.len(), 1, "{:?}", warnings);
+        assert!(warnings[0].contains("température"), "{}", warnings[0]);
+
+        let (_, warnings) = dataset_loader::merge_datasets_checked(
+            vec![forecast_point("Pluie")],
+            vec![forecast_point("Neige")],
+        );
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_forward_with_cache_checked_rejects_short_weights() {
+        let layer = Layer::new(
+            0,
+            "Cachée".to_string(),
+            vec![
+                Neuron::new(
+                    0,
+                    "C0".to_string(),
+                    ActivationFunction::Relu,
+                    0.0,
+                    vec![0.5, 0.5],
+                ),
+                Neuron::new(
+                    7,
+                    "C7".to_string(),
+                    ActivationFunction::Relu,
+                    0.0,
+                    vec![0.5],
+                ),
+            ],
+        );
+
+        let error = layer.forward_with_cache_checked(&[1.0, 2.0]).unwrap_err();
+        assert!(error.contains("neurone 7"), "{}", error);
+
+        let valid = Layer::new(
+            0,
+            "Cachée".to_string(),
+            vec![Neuron::new(
+                0,
+                "C0".to_string(),
+                ActivationFunction::Relu,
+                0.0,
+                vec![0.5, 0.5],
+            )],
+        );
+        assert_eq!(
+            valid.forward_with_cache_checked(&[1.0, 2.0]).unwrap(),
+            valid.forward_with_cache(&[1.0, 2.0])
+        );
+    }
+
+    #[test]
+    fn test_benchmark_inference_latency() {
+        let network = TrainingConfig::new(BinaryTrainer::default(), 4, vec![8, 4]).create_network();
+
+        let latency = bench::benchmark_inference(&network, &[0.2, 0.5, 0.1, 0.7], 1000);
+        assert!(latency > Duration::ZERO);
+        assert!(latency < Duration::from_millis(100), "{:?}", latency);
+
+        assert_eq!(
+            bench::benchmark_inference(&network, &[0.2, 0.5, 0.1, 0.7], 0),
+            Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn test_export_c_bakes_all_parameters() {
+        let network = TrainingConfig::new(BinaryTrainer::default(), 4, vec![3]).create_network();
+        let params = [0.0, 40.0, 950.0, 1050.0, 0.0, 2000.0, 0.0, 100.0];
+
+        let source = export::export_c(&network, &params).expect("échec de l'export C");
+        assert!(
+            source.contains(
+                "float predict(float temp, float pressure, float altitude, float humidity)"
+            )
+        );
+
+        // Every constant of the `static const float` arrays, weights and biases alike.
+        let constant_count: usize = source
+            .split("static const float")
+            .skip(1)
+            .map(|declaration| {
+                let start = declaration.find('{').unwrap();
+                let end = declaration.find("};").unwrap();
+                declaration[start..end]
+                    .split(',')
+                    .filter(|value| {
+                        !value
+                            .trim_matches(|c: char| c == '{' || c == '}' || c.is_whitespace())
+                            .is_empty()
+                    })
+                    .count()
+            })
+            .sum();
+        assert_eq!(constant_count, network.parameter_count());
+
+        let mut swish_network = network.clone();
+        swish_network.layers[0].neurons[1].activation_function = ActivationFunction::Swish;
+        let error = export::export_c(&swish_network, &params).unwrap_err();
+        assert!(error.contains("swish"), "{}", error);
+    }
+
+    #[test]
+    fn test_predict_with_uncertainty_mc_dropout() {
+        let network = TrainingConfig::new(BinaryTrainer::default(), 4, vec![16]).create_network();
+        let params = [0.0, 40.0, 950.0, 1050.0, 0.0, 2000.0, 0.0, 100.0];
+        let mut model = WeatherModel::new(network, params);
+        let input = WeatherInput {
+            temp: 12.0,
+            pressure: 1002.0,
+            altitude: 150.0,
+            humidity: 85.0,
+        };
+
+        let (mean, std) = model.predict_with_uncertainty(&input, 50).unwrap();
+        assert_eq!(std, 0.0, "sans dropout, tous les passages sont identiques");
+        assert!((mean - model.predict_proba(&input)).abs() < 1e-6);
+
+        model.dropout_rate = 0.5;
+        let (_, std) = model.predict_with_uncertainty(&input, 50).unwrap();
+        assert!(std > 0.0, "le dropout doit faire varier les sorties");
+        assert_eq!(model.predict_with_uncertainty(&input, 50).unwrap().1, std);
+    }
+
+    #[test]
+    fn test_discretize_one_hot_bins() {
+        let data = vec![SimplifiedWeatherDataPoint {
+            input: WeatherInput {
+                temp: 0.1,
+                pressure: 0.5,
+                altitude: 1.0,
+                humidity: f32::NAN,
+            },
+            output: true,
+        }];
+
+        let encoded = dataset_loader::discretize(&data, 4);
+        assert_eq!(encoded.len(), 1);
+        assert_eq!(encoded[0].len(), 16);
+        assert_eq!(
+            encoded[0],
+            vec![
+                1.0, 0.0, 0.0, 0.0, // temp 0.1 -> [0, 0.25)
+                0.0, 0.0, 1.0, 0.0, // pressure 0.5 -> [0.5, 0.75)
+                0.0, 0.0, 0.0, 1.0, // altitude 1.0 -> last bin
+                0.0, 0.0, 0.0, 0.0, // missing humidity
+            ]
+        );
+    }
+
+    #[test]
+    fn test_drop_constant_features() {
+        let data: Vec<SimplifiedWeatherDataPoint> = [(10.0, 1000.0, 40.0), (25.0, 1020.0, 90.0)]
+            .iter()
+            .map(|&(temp, pressure, humidity)| SimplifiedWeatherDataPoint {
+                input: WeatherInput {
+                    temp,
+                    pressure,
+                    altitude: 300.0,
+                    humidity,
+                },
+                output: humidity > 50.0,
+            })
+            .collect();
+
+        let (reduced, mask) = dataset_loader::drop_constant_features(&data);
+        assert_eq!(mask, vec![true, true, false, true]);
+        assert_eq!(reduced[1], vec![25.0, 1020.0, 90.0]);
+        assert_eq!(
+            dataset_loader::apply_feature_mask(&data[0].input, &mask),
+            reduced[0]
+        );
+    }
+
+    #[test]
+    fn test_balanced_sampling_oversamples_minority() {
+        let labels: Vec<bool> = (0..100).map(|i| i < 10).collect();
+        let mut rng = StdRng::seed_from_u64(5);
+
+        let uniform = SamplingStrategy::Uniform.epoch_indices(&labels, &mut rng);
+        let mut sorted = uniform.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..100).collect::<Vec<_>>());
+
+        let mut minority_draws = 0;
+        let mut total_draws = 0;
+        for _ in 0..50 {
+            let indices = SamplingStrategy::Balanced.epoch_indices(&labels, &mut rng);
+            assert_eq!(indices.len(), labels.len());
+            minority_draws += indices.iter().filter(|&&i| labels[i]).count();
+            total_draws += indices.len();
+        }
+        let minority_share = minority_draws as f32 / total_draws as f32;
+        assert!(
+            (minority_share - 0.5).abs() < 0.05,
+            "la classe minoritaire devrait représenter environ la moitié des tirages : {}",
+            minority_share
+        );
+
+        let mut trainer = BinaryTrainer::new(0.1, 5, 10);
+        trainer.seed = Some(1);
+        trainer.sampling = SamplingStrategy::Balanced;
+        let data = noisy_temperature_data(0.0);
+        let mut network = trainer.create_weather_network(4, &[3]);
+        let (_, history) = trainer.train_with_history(&mut network, &data, &data);
+        assert_eq!(history.len(), 5);
+    }
+
+    #[test]
+    fn test_lipschitz_bound() {
+        let layer = Layer::new(
+            0,
+            "Sortie".to_string(),
+            vec![
+                Neuron::new(
+                    0,
+                    "S0".to_string(),
+                    ActivationFunction::Linear,
+                    1.0,
+                    vec![3.0, 4.0],
+                ),
+                Neuron::new(
+                    1,
+                    "S1".to_string(),
+                    ActivationFunction::Linear,
+                    -2.0,
+                    vec![1.0, 1.0],
+                ),
+            ],
+        );
+        let mut network = NeuralNetwork::new(vec![layer]);
+        assert_eq!(network.lipschitz_bound(), 5.0);
+
+        network.add_layer(Layer::new(
+            1,
+            "Sortie".to_string(),
+            vec![Neuron::new(
+                0,
+                "S".to_string(),
+                ActivationFunction::Sigmoid,
+                0.0,
+                vec![0.0, 2.0],
+            )],
+        ));
+        assert_eq!(network.lipschitz_bound(), 10.0);
+    }
+
+    #[test]
+    fn test_reproduce_is_bit_identical() {
+        let data = noisy_temperature_data(0.0);
+        let config = TrainingConfig::new(BinaryTrainer::new(0.2, 15, 10), 4, vec![3]);
+
+        let (first, first_accuracy) = trainer::reproduce(&config, &data, &data);
+        let (second, second_accuracy) = trainer::reproduce(&config, &data, &data);
+        assert_eq!(first, second);
+        assert_eq!(first_accuracy, second_accuracy);
+
+        // A config read back from JSON reproduces the same network.
+        let json = serde_json::to_string(&config).unwrap();
+        let loaded: TrainingConfig = serde_json::from_str(&json).unwrap();
+        let (third, _) = trainer::reproduce(&loaded, &data, &data);
+        assert_eq!(third.get_parameters(), first.get_parameters());
+    }
+
+    #[test]
+    fn test_save_model_rejects_non_finite_weights() {
+        let mut network = temperature_threshold_network();
+        network.layers[0].neurons[0].weights[2] = f32::NAN;
+        let path = std::env::temp_dir().join("forecast_nn_nan_model_test.json");
+        std::fs::remove_file(&path).unwrap_or(());
+
+        let error = pickle::save_model(&network, &[0.0; 8], &path).unwrap_err();
+        assert!(error.to_string().contains("non fini"), "{}", error);
+        assert!(!path.exists(), "un modèle invalide ne doit pas être écrit");
+
+        network.layers[0].neurons[0].weights[2] = 0.0;
+        network.layers[0].neurons[0].bias = f32::INFINITY;
+        assert!(pickle::save_weights(&network, &path).is_err());
+
+        network.layers[0].neurons[0].bias = -5.0;
+        pickle::save_model(&network, &[0.0; 8], &path)
+            .expect("le modèle fini doit être sauvegardé");
+        std::fs::remove_file(&path).unwrap_or(());
+    }
+
+    #[test]
+    fn test_save_rejects_non_finite_config_and_ensemble_weights() {
+        let network = temperature_threshold_network();
+        let path = std::env::temp_dir().join("forecast_nn_nan_config_test.json");
+        std::fs::remove_file(&path).unwrap_or(());
+
+        let mut config = TrainingConfig::new(BinaryTrainer::default(), 4, vec![1]);
+        config.trainer.min_delta = f32::NAN;
+        let options = SaveOptions {
+            training_config: Some(config.clone()),
+            ..SaveOptions::default()
+        };
+        let error =
+            pickle::save_model_with_options(&network, &[0.0; 8], &options, &path).unwrap_err();
+        assert!(error.to_string().contains("non fini"), "{}", error);
+        assert!(
+            !path.exists(),
+            "une configuration invalide ne doit pas être écrite"
+        );
+
+        config.trainer.min_delta = 0.0;
+        config.optimizer = Some(OptimizerKind::AdamW {
+            beta1: 0.9,
+            beta2: 0.999,
+            epsilon: f32::INFINITY,
+            weight_decay: 0.01,
+        });
+        let options = SaveOptions {
+            training_config: Some(config),
+            ..SaveOptions::default()
+        };
+        assert!(pickle::save_model_with_options(&network, &[0.0; 8], &options, &path).is_err());
+
+        let model = WeatherModel::new(network, [0.0; 8]);
+        let mut ensemble = Ensemble::new(vec![model]).unwrap();
+        ensemble.weights[0] = f32::NAN;
+        assert!(pickle::save_ensemble(&ensemble, &path).is_err());
+        assert!(
+            !path.exists(),
+            "un ensemble invalide ne doit pas être écrit"
+        );
+    }
+
+    #[test]
+    fn test_feature_gradient_matches_input_gradient() {
+        let network = TrainingConfig::new(BinaryTrainer::default(), 4, vec![5]).create_network();
+        let inputs = [0.3, 0.7, 0.1, 0.9];
+
+        let full_gradient = network.input_gradient(&inputs);
+        for (feature_index, &expected) in full_gradient.iter().enumerate() {
+            assert_eq!(network.feature_gradient(&inputs, feature_index), expected);
+        }
+    }
+
+    #[test]
+    fn test_weight_clip_bounds_parameters() {
+        let mut trainer = BinaryTrainer::new(0.5, 1, 10);
+        trainer.seed = Some(4);
+        trainer.weight_clip = Some(0.05);
+        let data = noisy_temperature_data(0.0);
+        let mut network = trainer.create_weather_network(4, &[3]);
+        assert!(network.get_parameters().iter().any(|p| p.abs() > 0.05));
+
+        trainer.train(&mut network, &data, &data);
+        assert!(
+            network.get_parameters().iter().all(|p| p.abs() <= 0.05),
+            "aucun paramètre ne doit dépasser la borne de découpage"
+        );
+
+        let mut optimizer = MomentumOptimizer::new(0.9, false);
+        let mut network = trainer.create_weather_network(4, &[3]);
+        trainer.train_with_optimizer(&mut network, &data, &data, &mut optimizer);
+        assert!(network.get_parameters().iter().all(|p| p.abs() <= 0.05));
+    }
+
+    #[test]
+    fn test_load_simplified_dataset() {
+        let path = std::env::temp_dir().join("forecast_nn_simplified_dataset_test.json");
+        std::fs::write(
+            &path,
+            r#"[
+                {"input": {"temp": 12.5, "pressure": 1002.0, "altitude": 150.0, "humidity": 88.0}, "output": true},
+                {"input": {"temp": 24.0, "pressure": 1021.0, "altitude": 300.0, "humidity": null}, "output": false}
+            ]"#,
+        )
+        .unwrap();
+
+        let data = dataset_loader::load_simplified_dataset(&path).expect("échec du chargement");
+        std::fs::remove_file(&path).unwrap_or(());
+
+        assert_eq!(data.len(), 2);
+        assert!(data[0].output);
+        assert!(!data[1].output);
+        assert_eq!(data[0].input.temp, 12.5);
+        assert!(data[1].input.humidity.is_nan());
+    }
+
+    #[test]
+    fn test_predict_topk_softmax_ranking() {
+        // Linear outputs equal to the biases: logits [ln 1, ln 5, ln 3, ln 1].
+        let logits = [1.0f32, 5.0, 3.0, 1.0].map(f32::ln);
+        let outputs: Vec<Neuron> = logits
+            .iter()
+            .enumerate()
+            .map(|(i, &logit)| {
+                Neuron::new(
+                    i as u32,
+                    format!("Sortie_{}", i),
+                    ActivationFunction::Linear,
+                    logit,
+                    vec![0.0, 0.0],
+                )
+            })
+            .collect();
+        let network = NeuralNetwork::new(vec![Layer::new(0, "Sortie".to_string(), outputs)]);
+
+        let top2 = network.predict_topk(&[0.4, 0.6], 2);
+        assert_eq!(top2.len(), 2);
+        assert_eq!((top2[0].0, top2[1].0), (1, 2));
+        assert!((top2[0].1 - 0.5).abs() < 1e-6, "{:?}", top2);
+        assert!((top2[1].1 - 0.3).abs() < 1e-6, "{:?}", top2);
+
+        let all = network.predict_topk(&[0.4, 0.6], 10);
+        assert_eq!(
+            all.iter().map(|&(i, _)| i).collect::<Vec<_>>(),
+            vec![1, 2, 0, 3]
+        );
+        assert!((all.iter().map(|&(_, p)| p).sum::<f32>() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_zero_batch_size_is_rejected() {
+        let trainer = BinaryTrainer::new(0.05, 10, 0);
+        assert!(trainer.validate().is_err());
+        assert!(BinaryTrainer::new(0.05, 10, 1).validate().is_ok());
+
+        let args: Vec<String> = ["train", "--batch-size", "0"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        let error = cli::run(&args).unwrap_err();
+        assert!(
+            error.to_string().contains("taille de lot"),
+            "Erreur : {}",
+            error
+        );
+
+        let data = noisy_temperature_data(0.0);
+        let result = std::panic::catch_unwind(|| {
+            let mut network = trainer.create_weather_network(4, &[2]);
+            trainer.train(&mut network, &data, &data)
+        });
+        assert!(result.is_err(), "un lot vide ne doit pas être accepté");
+    }
+
+    #[test]
+    fn test_evaluate_with_errors() {
+        let network = temperature_threshold_network();
+        let point = |temp: f32, output: bool| SimplifiedWeatherDataPoint {
+            input: WeatherInput {
+                temp,
+                pressure: 0.5,
+                altitude: 0.5,
+                humidity: 0.5,
+            },
+            output,
+        };
+        let data = vec![
+            point(0.9, true),
+            point(0.1, false),
+            point(0.2, true),
+            point(0.8, false),
+        ];
+
+        let (accuracy, errors) = metrics::evaluate_with_errors(&network, &data, 0.5);
+        assert!((accuracy - 0.5).abs() < 1e-6);
+        let misclassified: Vec<(f32, bool)> = errors
+            .iter()
+            .map(|data_point| (data_point.input.temp, data_point.output))
+            .collect();
+        assert_eq!(misclassified, vec![(0.2, true), (0.8, false)]);
+
+        let (accuracy, errors) = metrics::evaluate_with_errors(&network, &[], 0.5);
+        assert_eq!(accuracy, 0.0);
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn test_activate_final_matches_last_layer() {
+        let hidden = Layer::new_mixed(
+            0,
+            "Cachée".to_string(),
+            &[
+                (ActivationFunction::Relu, vec![0.5, -0.2]),
+                (ActivationFunction::Tanh, vec![-0.4, 0.9]),
+            ],
+        );
+        let output = Layer::new(
+            1,
+            "Sortie".to_string(),
+            vec![Neuron::new(
+                0,
+                "Sortie".to_string(),
+                ActivationFunction::Sigmoid,
+                0.1,
+                vec![1.2, -0.7],
+            )],
+        );
+        let network = NeuralNetwork::new(vec![hidden, output]);
+        let input = [0.6, 0.3];
+
+        let final_output = network.activate_final(&input);
+        assert_eq!(&final_output, network.activate(&input).last().unwrap());
+
+        let mut buffers = ForwardBuffers::default();
+        network.forward_with_buffers(&input, &mut buffers);
+        assert_eq!(&final_output, buffers.layer_outputs.last().unwrap());
+    }
+
+    #[test]
+    fn test_metrics_agree_at_threshold() {
+        let model = constant_model(0.5);
+        let network = &model.network;
+        let inputs = vec![vec![0.5; 4]];
+        let targets = vec![vec![1.0]];
+
+        let at_least = metrics::confusion_matrix(network, &inputs, &targets, 0.5);
+        assert_eq!(at_least.true_positives, 1);
+        let greater_than = metrics::confusion_matrix_with_bound(
+            network,
+            &inputs,
+            &targets,
+            0.5,
+            InclusiveBound::GreaterThan,
+        );
+        assert_eq!(greater_than.false_negatives, 1);
+
+        for bound in [InclusiveBound::AtLeast, InclusiveBound::GreaterThan] {
+            let accuracy =
+                metrics::binary_accuracy_with_bound(network, &inputs, &targets, 0.5, bound);
+            let report =
+                metrics::classification_report_with_bound(network, &inputs, &targets, 0.5, bound);
+            let sweep =
+                metrics::threshold_sweep_with_bound(network, &inputs, &targets, &[0.5], bound);
+            let (_, precipitation_accuracy) =
+                metrics::per_class_accuracy_with_bound(network, &inputs, &targets, 0.5, bound);
+            assert_eq!(report.accuracy, accuracy);
+            assert_eq!(sweep[0].accuracy, accuracy);
+            assert_eq!(precipitation_accuracy, accuracy);
+        }
+
+        assert_eq!(
+            metrics::classification_report(network, &inputs, &targets, 0.5).accuracy,
+            1.0
+        );
+        assert_eq!(
+            metrics::threshold_sweep(network, &inputs, &targets, &[0.5])[0].accuracy,
+            1.0
+        );
+        assert_eq!(
+            metrics::per_class_accuracy(network, &inputs, &targets, 0.5),
+            (0.0, 1.0)
+        );
+
+        let input = WeatherInput {
+            temp: 20.0,
+            pressure: 1015.0,
+            altitude: 300.0,
+            humidity: 60.0,
+        };
+        let data = vec![SimplifiedWeatherDataPoint {
+            input: WeatherInput {
+                temp: 0.5,
+                pressure: 0.5,
+                altitude: 0.5,
+                humidity: 0.5,
+            },
+            output: true,
+        }];
+        let (accuracy, errors) = metrics::evaluate_with_errors(network, &data, 0.5);
+        assert_eq!(accuracy, 1.0);
+        assert!(errors.is_empty());
+
+        let predictions = weather_model::predict_dataset(
+            network,
+            &model.normalization_params,
+            std::slice::from_ref(&input),
+        );
+        assert!(predictions[0].label);
+
+        let shared = SharedModel::new(model);
+        assert!(shared.predict(&input, 0.5));
+        assert!(!shared.predict_with_bound(&input, 0.5, InclusiveBound::GreaterThan));
+    }
+
+    #[test]
+    fn test_early_stopping_accumulates_sub_min_delta_gains() {
+        // At this rate the validation loss drops by about 5e-5 per epoch: no single epoch
+        // beats `min_delta`, but every two or three epochs together do.
+        let data = clean_temperature_data();
+        let mut trainer = BinaryTrainer::new(0.001, 60, 60);
+        trainer.seed = Some(1);
+        trainer.monitor = StopMetric::Loss;
+        trainer.min_delta = 1e-4;
+        let mut network = temperature_threshold_network();
+        network.layers[0].neurons[0].weights = vec![0.0; 4];
+        network.layers[0].neurons[0].bias = 0.0;
+
+        let (_, history) = trainer.train_with_history(&mut network, &data, &data);
+        assert_eq!(
+            history.len(),
+            60,
+            "l'arrêt anticipé ne doit pas se déclencher"
+        );
+    }
+
+    #[test]
+    fn test_reproduce_uses_saved_init_and_optimizer() {
+        let data = noisy_temperature_data(0.0);
+        let checkpoint_path =
+            std::env::temp_dir().join("forecast_nn_reproduce_checkpoint_test.json");
+        std::fs::remove_file(&checkpoint_path).unwrap_or(());
+
+        let mut trainer = BinaryTrainer::new(0.2, 15, 10);
+        trainer.seed = Some(7);
+        trainer.checkpoint_path = Some(checkpoint_path.clone());
+        let mut config = TrainingConfig::new(trainer.clone(), 4, vec![3]);
+        config.init_config = InitConfig {
+            scheme: InitScheme::HeUniform,
+            bias_range: (-0.3, 0.2),
+            seed: Some(11),
+            ..InitConfig::default()
+        };
+        config.optimizer = Some(OptimizerKind::Momentum {
+            momentum: 0.9,
+            nesterov: false,
+        });
+
+        let (reproduced, reproduced_accuracy) = trainer::reproduce(&config, &data, &data);
+        assert!(
+            !checkpoint_path.exists(),
+            "reproduce ne doit pas écrire de point de contrôle"
+        );
+
+        trainer.checkpoint_path = None;
+        let mut network = config.create_network();
+        let accuracy = trainer.train_with_optimizer(
+            &mut network,
+            &data,
+            &data,
+            &mut MomentumOptimizer::new(0.9, false),
+        );
+        assert_eq!(reproduced.get_parameters(), network.get_parameters());
+        assert_eq!(reproduced_accuracy, accuracy);
+
+        let json = serde_json::to_string(&config).unwrap();
+        let loaded: TrainingConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, config);
+    }
+
+    #[test]
+    fn test_checkpointing_requires_normalization() {
+        let checkpoint_path =
+            std::env::temp_dir().join("forecast_nn_checkpoint_normalization_test.json");
+        std::fs::remove_file(&checkpoint_path).unwrap_or(());
+
+        let mut trainer = BinaryTrainer::new(0.5, 5, 10);
+        trainer.checkpoint_path = Some(checkpoint_path.clone());
+        let error = trainer.validate().unwrap_err();
+        assert!(error.contains("normalisation"), "Erreur : {}", error);
+
+        let data = noisy_temperature_data(0.0);
+        let result = std::panic::catch_unwind(|| {
+            let mut network = trainer.create_weather_network(4, &[2]);
+            trainer.train(&mut network, &data, &data)
+        });
+        assert!(result.is_err());
+        assert!(!checkpoint_path.exists());
+
+        let params = [0.0, 40.0, 950.0, 1050.0, 0.0, 2000.0, 0.0, 100.0];
+        trainer.normalization_params = Some(params);
+        let mut network = trainer.create_weather_network(4, &[2]);
+        trainer.train(&mut network, &data, &data);
+        let checkpoint = pickle::load_saved_model(&checkpoint_path).unwrap();
+        assert_eq!(checkpoint.normalization_params, params);
+
+        std::fs::remove_file(&checkpoint_path).unwrap_or(());
+    }
+
+    #[test]
+    fn test_predict_with_uncertainty_rejects_invalid_rates() {
+        let network = TrainingConfig::new(BinaryTrainer::default(), 4, vec![4]).create_network();
+        let mut model =
+            WeatherModel::new(network, [0.0, 40.0, 950.0, 1050.0, 0.0, 2000.0, 0.0, 100.0]);
+        let input = WeatherInput {
+            temp: 12.0,
+            pressure: 1002.0,
+            altitude: 150.0,
+            humidity: 85.0,
+        };
+
+        for rate in [1.0, -0.1, f32::NAN] {
+            model.dropout_rate = rate;
+            assert!(
+                model.predict_with_uncertainty(&input, 10).is_err(),
+                "taux {}",
+                rate
+            );
+        }
+
+        // Without dropout, each pass is the temperature-scaled probability.
+        model.dropout_rate = 0.0;
+        model.set_temperature(2.5).unwrap();
+        let (mean, std) = model.predict_with_uncertainty(&input, 10).unwrap();
+        assert_eq!(std, 0.0);
+        assert!((mean - model.predict_proba(&input)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_saved_model_keeps_temperature_and_dropout() {
+        let mut model = WeatherModel::new(
+            temperature_threshold_network(),
+            [0.0, 40.0, 950.0, 1050.0, 0.0, 2000.0, 0.0, 100.0],
+        );
+        model.set_temperature(1.7).unwrap();
+        model.dropout_rate = 0.3;
+
+        for temperature in [0.0, -1.0, f32::NAN, f32::INFINITY] {
+            assert!(
+                model.set_temperature(temperature).is_err(),
+                "température {}",
+                temperature
+            );
+        }
+        assert_eq!(model.temperature, 1.7);
+
+        let path = std::env::temp_dir().join("forecast_nn_temperature_dropout_test.json");
+        let options = SaveOptions {
+            temperature: model.temperature,
+            dropout_rate: model.dropout_rate,
+            ..SaveOptions::default()
+        };
+        pickle::save_model_with_options(
+            &model.network,
+            &model.normalization_params,
+            &options,
+            &path,
+        )
+        .unwrap();
+        let reloaded = WeatherModel::from(pickle::load_saved_model(&path).unwrap());
+        assert_eq!(reloaded, model);
+        std::fs::remove_file(&path).unwrap_or(());
+
+        let round_trip = WeatherModel::from(pickle::SavedModel::from(&model));
+        assert_eq!(round_trip, model);
+
+        // Models saved before these fields existed load with the defaults.
+        let json = serde_json::json!({
+            "network": model.network,
+            "normalization_params": model.normalization_params,
+        });
+        let legacy: pickle::SavedModel = serde_json::from_value(json).unwrap();
+        assert_eq!(legacy.temperature, 1.0);
+        assert_eq!(legacy.dropout_rate, 0.0);
+    }
+
+    #[test]
+    fn test_calibrated_temperature_survives_save() {
+        let neuron = Neuron::new(
+            0,
+            "Sortie".to_string(),
+            ActivationFunction::Sigmoid,
+            -20.0,
+            vec![40.0, 0.0, 0.0, 0.0],
+        );
+        let network = NeuralNetwork::new(vec![Layer::new(0, "Sortie".to_string(), vec![neuron])]);
+        let mut model = WeatherModel::new(network, [0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0]);
+
+        assert!(weather_model::calibrate_temperature(&mut model, &[]).is_err());
+        assert_eq!(model.temperature, 1.0);
+
+        let temperature =
+            weather_model::calibrate_temperature(&mut model, &noisy_temperature_data(0.75))
+                .unwrap();
+        let path = std::env::temp_dir().join("forecast_nn_calibrated_model_test.json");
+        let options = SaveOptions {
+            temperature: model.temperature,
+            ..SaveOptions::default()
+        };
+        pickle::save_model_with_options(
+            &model.network,
+            &model.normalization_params,
+            &options,
+            &path,
+        )
+        .unwrap();
+        let reloaded = WeatherModel::from(pickle::load_saved_model(&path).unwrap());
+        std::fs::remove_file(&path).unwrap_or(());
+
+        assert_eq!(reloaded.temperature, temperature);
+        let input = WeatherInput {
+            temp: 0.55,
+            pressure: 0.5,
+            altitude: 0.5,
+            humidity: 0.5,
+        };
+        assert_eq!(reloaded.predict_proba(&input), model.predict_proba(&input));
+    }
+
+    #[test]
+    fn test_init_config_validation_and_exclusive_ranges() {
+        let invalid = [
+            InitConfig {
+                bias_range: (0.3, 0.2),
+                ..InitConfig::default()
+            },
+            InitConfig {
+                bias_range: (0.1, 0.1),
+                ..InitConfig::default()
+            },
+            InitConfig {
+                weight_scale: Some(0.0),
+                ..InitConfig::default()
+            },
+            InitConfig {
+                weight_scale: Some(-0.5),
+                ..InitConfig::default()
+            },
+            InitConfig {
+                weight_scale: Some(f32::NAN),
+                ..InitConfig::default()
+            },
+        ];
+        for config in &invalid {
+            assert!(config.validate().is_err(), "{:?}", config);
+        }
+        let trainer = BinaryTrainer::default();
+        let result = std::panic::catch_unwind(|| {
+            trainer.create_weather_network_with_config(4, &[2], &invalid[0])
+        });
+        assert!(result.is_err());
+
+        // Seeded networks draw from the same half-open ranges as before `InitConfig` existed.
+        let init_config = InitConfig {
+            seed: Some(5),
+            ..InitConfig::default()
+        };
+        let network = trainer.create_weather_network_with_config(4, &[], &init_config);
+        let mut rng = StdRng::seed_from_u64(5);
+        let bound = (6.0_f32 / 5.0).sqrt();
+        let weights: Vec<f32> = (0..4).map(|_| rng.random_range(-bound..bound)).collect();
+        let bias = rng.random_range(-0.1..0.1);
+        assert_eq!(network.layers[0].neurons[0].weights, weights);
+        assert_eq!(network.layers[0].neurons[0].bias, bias);
+
+        let init_config = InitConfig {
+            weight_scale: Some(0.5),
+            bias_range: (0.2, 0.3),
+            seed: Some(5),
+            ..InitConfig::default()
+        };
+        let network = trainer.create_weather_network_with_config(4, &[], &init_config);
+        let mut rng = StdRng::seed_from_u64(5);
+        let weights: Vec<f32> = (0..4).map(|_| rng.random_range(-0.5..0.5)).collect();
+        let bias = rng.random_range(0.2..0.3);
+        assert_eq!(network.layers[0].neurons[0].weights, weights);
+        assert_eq!(network.layers[0].neurons[0].bias, bias);
+    }
+
+    #[test]
+    fn test_ensemble_rejects_invalid_members() {
+        assert!(Ensemble::new(vec![]).is_err());
+        assert!(Ensemble::with_weights(vec![], vec![]).is_err());
+
+        for weights in [
+            vec![f32::NAN, 1.0],
+            vec![f32::INFINITY, 1.0],
+            vec![-1.0, 2.0],
+            vec![0.0, 0.0],
+            vec![1.0],
+        ] {
+            let models = vec![constant_model(0.3), constant_model(0.7)];
+            assert!(
+                Ensemble::with_weights(models, weights.clone()).is_err(),
+                "poids {:?}",
+                weights
+            );
+        }
+
+        let models = vec![constant_model(0.3), constant_model(0.7)];
+        assert!(Ensemble::with_weights(models, vec![0.0, 1.0]).is_ok());
+    }
+
+    #[test]
+    fn test_network_depth_and_max_width() {
+        let network = BinaryTrainer::new(0.05, 10, 20).create_weather_network(4, &[8, 4]);
+        assert_eq!(network.depth(), 3);
+        assert_eq!(network.max_width(), 8);
+    }
+
+    #[test]
+    fn test_forward_into_asserts_weight_count_in_debug() {
+        let layer = Layer::new(
+            0,
+            "Cachée".to_string(),
+            vec![Neuron::new(
+                3,
+                "C3".to_string(),
+                ActivationFunction::Relu,
+                0.0,
+                vec![0.5],
+            )],
+        );
+        let network = NeuralNetwork::new(vec![layer]);
+
+        let result = std::panic::catch_unwind(|| {
+            network.forward_with_buffers(&[1.0, 2.0], &mut ForwardBuffers::default())
+        });
+        assert_eq!(result.is_err(), cfg!(debug_assertions));
+    }
+
+    #[test]
+    fn test_invalid_weight_clip_is_rejected() {
+        let data = clean_temperature_data();
+        for limit in [-0.05, 0.0, f32::NAN, f32::INFINITY] {
+            let mut trainer = BinaryTrainer::new(0.05, 5, 10);
+            trainer.weight_clip = Some(limit);
+            assert!(trainer.validate().is_err(), "limite {}", limit);
+
+            let result = std::panic::catch_unwind(|| {
+                let mut network = trainer.create_weather_network(4, &[2]);
+                trainer.train(&mut network, &data, &data)
+            });
+            assert!(result.is_err(), "limite {}", limit);
+        }
+
+        let mut trainer = BinaryTrainer::new(0.05, 5, 10);
+        trainer.weight_clip = Some(0.05);
+        assert!(trainer.validate().is_ok());
+    }
+
+    #[test]
+    fn test_backward_hidden_layers_follow_true_gradient() {
+        // The hidden gradients used to skip the activation derivative and to be propagated
+        // through the output weights after they had already been updated, so a two-layer
+        // network drifted away from gradient descent on its own loss.
+        let hidden = Layer::new_mixed(
+            0,
+            "Cachée".to_string(),
+            &[
+                (ActivationFunction::Sigmoid, vec![0.4, -0.3]),
+                (ActivationFunction::Tanh, vec![0.2, 0.5]),
+            ],
+        );
+        let output = Layer::new(
+            1,
+            "Sortie".to_string(),
+            vec![Neuron::new(
+                0,
+                "Sortie".to_string(),
+                ActivationFunction::Sigmoid,
+                0.05,
+                vec![0.7, -0.6],
+            )],
+        );
+        let network = NeuralNetwork::new(vec![hidden, output]);
+        let (input, target) = ([0.3, 0.9], [1.0]);
+
+        let mut updated = network.clone();
+        updated.backward(&input, &target, 0.5);
+
+        let (_, gradients) =
+            network.backward_no_update(&input, &target, LossFunction::MeanSquaredError);
+        let mut manual = network.clone();
+        manual.apply_gradients(&gradients, 0.5);
+
+        for (manual, updated) in manual.get_parameters().iter().zip(updated.get_parameters()) {
+            assert!(
+                (manual - updated).abs() < 1e-6,
+                "backward doit suivre le vrai gradient : {} != {}",
+                manual,
+                updated
+            );
+        }
+    }
 }